@@ -0,0 +1,155 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Attribute, Expr, Result};
+
+/// Byte order selected by a `le` or `be` attribute
+#[derive(Copy, Clone)]
+pub(crate) enum Order {
+    Little,
+    Big,
+}
+
+impl ToTokens for Order {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Order::Little => quote!(::scursor::LittleEndian),
+            Order::Big => quote!(::scursor::BigEndian),
+        });
+    }
+}
+
+/// Primitive integer and byte order specified as a single identifier, e.g. `u16_be`
+pub(crate) struct PrimitiveSpec {
+    pub(crate) ty: Ident,
+    pub(crate) order: Order,
+}
+
+impl PrimitiveSpec {
+    fn parse(ident: &Ident) -> Result<Self> {
+        let text = ident.to_string();
+        let (ty, order) = match text.split_once('_') {
+            Some((ty, "le")) => (ty, Some(Order::Little)),
+            Some((ty, "be")) => (ty, Some(Order::Big)),
+            Some(_) => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "byte order suffix must be `_le` or `_be`",
+                ))
+            }
+            None => (text.as_str(), None),
+        };
+        let order = match (ty, order) {
+            ("u8" | "i8", order) => order.unwrap_or(Order::Little),
+            ("u16" | "i16" | "u32" | "i32" | "u64" | "i64", Some(order)) => order,
+            ("u16" | "i16" | "u32" | "i32" | "u64" | "i64", None) => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "multi-byte integers require a `_le` or `_be` suffix",
+                ))
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "expected an integer type such as `u8` or `u16_be`",
+                ))
+            }
+        };
+        Ok(Self {
+            ty: Ident::new(ty, ident.span()),
+            order,
+        })
+    }
+
+    /// Unsigned integer type of the same width, used to report a value without sign extension
+    pub(crate) fn unsigned(&self) -> Ident {
+        let text = self.ty.to_string();
+        let unsigned = text.strip_prefix('i').map(|bits| format!("u{bits}"));
+        Ident::new(unsigned.as_deref().unwrap_or(&text), self.ty.span())
+    }
+}
+
+/// Attributes that may be applied to a struct or enum
+#[derive(Default)]
+pub(crate) struct ContainerAttrs {
+    pub(crate) tag: Option<PrimitiveSpec>,
+}
+
+impl ContainerAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("scursor")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tag") {
+                    let ident: Ident = meta.value()?.parse()?;
+                    result.tag = Some(PrimitiveSpec::parse(&ident)?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported container attribute"))
+                }
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+/// Attributes that may be applied to an enum variant
+#[derive(Default)]
+pub(crate) struct VariantAttrs {
+    pub(crate) tag: Option<Expr>,
+}
+
+impl VariantAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("scursor")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tag") {
+                    result.tag = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported variant attribute"))
+                }
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+/// Attributes that may be applied to a field
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    pub(crate) order: Option<Order>,
+    pub(crate) magic: Option<Expr>,
+    pub(crate) prefix: Option<PrimitiveSpec>,
+}
+
+impl FieldAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("scursor")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("le") || meta.path.is_ident("be") {
+                    if result.order.is_some() {
+                        return Err(meta.error("byte order specified more than once"));
+                    }
+                    result.order = Some(if meta.path.is_ident("le") {
+                        Order::Little
+                    } else {
+                        Order::Big
+                    });
+                    Ok(())
+                } else if meta.path.is_ident("magic") {
+                    result.magic = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("prefix") {
+                    let ident: Ident = meta.value()?.parse()?;
+                    result.prefix = Some(PrimitiveSpec::parse(&ident)?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported field attribute"))
+                }
+            })?;
+        }
+        Ok(result)
+    }
+}
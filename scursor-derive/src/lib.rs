@@ -0,0 +1,426 @@
+//! Derive macros for the `Decode` and `Encode` traits of the [scursor](https://docs.rs/scursor) library
+//!
+//! These macros are re-exported by `scursor` when its `derive` feature is enabled.
+//! Fields are decoded and encoded in declaration order. Each field must implement
+//! `Decode` / `Encode` unless one of the following `#[scursor(...)]` attributes changes
+//! how it is handled:
+//!
+//! * `le` or `be` - the field is a primitive read and written in the given byte order
+//! * `magic = <expr>` - the field must equal the expression when decoded and the
+//!   expression is always written when encoded
+//! * `prefix = <spec>` - the field is preceded by its encoded length in bytes, where
+//!   `<spec>` is an integer type and byte order such as `u8`, `u16_be` or `u32_le`
+//!
+//! A borrowed `&[u8]` field consumes all of the remaining input when decoded, so it
+//! must either be the last field or have a `prefix` attribute:
+//!
+//! ```compile_fail
+//! use scursor::Decode;
+//!
+//! #[derive(Decode)]
+//! struct Message<'a> {
+//!     payload: &'a [u8],
+//!     crc: u8,
+//! }
+//! ```
+//!
+//! Enums require a container attribute `#[scursor(tag = <spec>)]` specifying the type
+//! of the discriminant that precedes the fields of each variant. The value of the
+//! discriminant is taken from a `#[scursor(tag = <expr>)]` variant attribute or from
+//! the explicit discriminant of the variant.
+
+mod attr;
+
+use attr::{ContainerAttrs, FieldAttrs, PrimitiveSpec, VariantAttrs};
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Data, DeriveInput, Expr, Fields, GenericParam, Lifetime, LifetimeParam,
+    Result, Type,
+};
+
+/// Derive the `Decode` trait
+#[proc_macro_derive(Decode, attributes(scursor))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive the `Encode` trait
+#[proc_macro_derive(Encode, attributes(scursor))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct Field<'a> {
+    member: Option<&'a Ident>,
+    ty: &'a Type,
+    attrs: FieldAttrs,
+    binding: Ident,
+}
+
+struct Variant<'a> {
+    ident: &'a Ident,
+    tag: Expr,
+    fields: &'a Fields,
+}
+
+/// Return true if the type is a borrowed byte slice
+fn is_byte_slice(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => match &*reference.elem {
+            Type::Slice(slice) => {
+                matches!(&*slice.elem, Type::Path(path) if path.path.is_ident("u8"))
+            }
+            _ => false,
+        },
+        Type::Group(group) => is_byte_slice(&group.elem),
+        Type::Paren(paren) => is_byte_slice(&paren.elem),
+        _ => false,
+    }
+}
+
+fn parse_fields(fields: &Fields) -> Result<Vec<Field<'_>>> {
+    let last = fields.len().saturating_sub(1);
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let attrs = FieldAttrs::parse(&field.attrs)?;
+            if attrs.order.is_some() && attrs.prefix.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "`prefix` cannot be combined with `le` or `be`",
+                ));
+            }
+            // an unprefixed slice would consume the fields that follow it
+            if index != last && attrs.prefix.is_none() && is_byte_slice(&field.ty) {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "a `&[u8]` field that is not last requires a `prefix` attribute",
+                ));
+            }
+            Ok(Field {
+                member: field.ident.as_ref(),
+                ty: &field.ty,
+                attrs,
+                binding: format_ident!("__field{}", index),
+            })
+        })
+        .collect()
+}
+
+fn parse_variants(input: &DeriveInput) -> Result<(PrimitiveSpec, Vec<Variant<'_>>)> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => unreachable!("only called for enums"),
+    };
+    let tag = ContainerAttrs::parse(&input.attrs)?.tag.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "enums require a `#[scursor(tag = <type>)]` attribute",
+        )
+    })?;
+    let variants = data
+        .variants
+        .iter()
+        .map(|variant| {
+            let attrs = VariantAttrs::parse(&variant.attrs)?;
+            let tag = match (attrs.tag, &variant.discriminant) {
+                (Some(tag), _) => tag,
+                (None, Some((_, discriminant))) => discriminant.clone(),
+                (None, None) => {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "variant requires a `#[scursor(tag = <value>)]` attribute or an explicit discriminant",
+                    ))
+                }
+            };
+            Ok(Variant {
+                ident: &variant.ident,
+                tag,
+                fields: &variant.fields,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((tag, variants))
+}
+
+fn check_container(input: &DeriveInput) -> Result<()> {
+    match &input.data {
+        Data::Struct(_) => {
+            if ContainerAttrs::parse(&input.attrs)?.tag.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "`tag` is only supported on enums",
+                ));
+            }
+            Ok(())
+        }
+        Data::Enum(_) => Ok(()),
+        Data::Union(_) => Err(syn::Error::new_spanned(
+            &input.ident,
+            "unions are not supported",
+        )),
+    }
+}
+
+/// Return a pattern that binds each field of a struct or variant by reference
+fn bind_fields(path: TokenStream2, fields: &Fields, parsed: &[Field]) -> TokenStream2 {
+    let bindings = parsed.iter().map(|field| {
+        // fixed values are always written from their expression
+        let binding = if field.attrs.magic.is_some() {
+            quote!(_)
+        } else {
+            let binding = &field.binding;
+            quote!(#binding)
+        };
+        match field.member {
+            Some(member) => quote!(#member: #binding),
+            None => binding,
+        }
+    });
+    match fields {
+        Fields::Named(_) => quote!(#path { #(#bindings),* }),
+        Fields::Unnamed(_) => quote!(#path ( #(#bindings),* )),
+        Fields::Unit => path,
+    }
+}
+
+/// Return an expression that constructs a struct or variant from the decoded bindings
+fn construct(path: TokenStream2, fields: &Fields, parsed: &[Field]) -> TokenStream2 {
+    let values = parsed.iter().map(|field| {
+        let binding = &field.binding;
+        match field.member {
+            Some(member) => quote!(#member: #binding),
+            None => quote!(#binding),
+        }
+    });
+    match fields {
+        Fields::Named(_) => quote!(#path { #(#values),* }),
+        Fields::Unnamed(_) => quote!(#path ( #(#values),* )),
+        Fields::Unit => path,
+    }
+}
+
+fn decode_field(field: &Field, lifetime: &Lifetime) -> TokenStream2 {
+    let ty = field.ty;
+    let binding = &field.binding;
+
+    let mut value = match field.attrs.order {
+        Some(order) => quote!(cursor.read_primitive::<#ty, #order>()?),
+        None => quote!(<#ty as ::scursor::Decode<#lifetime>>::decode(cursor)?),
+    };
+
+    if let Some(magic) = &field.attrs.magic {
+        value = quote!({
+            let __scursor_position = cursor.position();
+            let __scursor_value: #ty = #value;
+            if __scursor_value != (#magic) {
+                return ::core::result::Result::Err(::scursor::ReadError::BadMagic {
+                    position: __scursor_position,
+                });
+            }
+            __scursor_value
+        });
+    }
+
+    if let Some(PrimitiveSpec { ty: prefix, order }) = &field.attrs.prefix {
+        value = quote!({
+            let mut __scursor_section = cursor.read_length_prefixed::<#prefix, #order>()?;
+            let cursor = &mut __scursor_section;
+            let __scursor_value = #value;
+            if !cursor.is_empty() {
+                return ::core::result::Result::Err(::scursor::ReadError::UnconsumedBytes {
+                    position: cursor.position(),
+                    count: cursor.remaining(),
+                });
+            }
+            __scursor_value
+        });
+    }
+
+    quote!(let #binding = #value;)
+}
+
+fn encode_field(field: &Field) -> TokenStream2 {
+    let ty = field.ty;
+    let binding = &field.binding;
+
+    // fixed values are always written from their expression
+    let (setup, value, reference) = match &field.attrs.magic {
+        Some(magic) => (
+            quote!(let __scursor_magic: #ty = #magic;),
+            quote!(__scursor_magic),
+            quote!(&__scursor_magic),
+        ),
+        None => (quote!(), quote!(*#binding), quote!(#binding)),
+    };
+
+    let write = match (field.attrs.order, &field.attrs.prefix) {
+        (Some(order), _) => quote! {
+            ::scursor::Writer::write_primitive::<#ty, #order>(cursor, #value)?;
+        },
+        (None, Some(PrimitiveSpec { ty: prefix, order })) => quote! {
+            ::scursor::Writer::write_prefixed::<#prefix, #order, #ty>(cursor, #reference)?;
+        },
+        (None, None) => quote! {
+            ::scursor::Encode::encode(#reference, cursor)?;
+        },
+    };
+
+    quote!({
+        #setup
+        #write
+    })
+}
+
+fn expand_decode(input: &DeriveInput) -> Result<TokenStream2> {
+    check_container(input)?;
+
+    // types that borrow from the input use their first lifetime for decoding
+    let mut generics = input.generics.clone();
+    let lifetime = match input.generics.lifetimes().next() {
+        Some(param) => param.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'__scursor", Span::call_site());
+            generics.params.insert(
+                0,
+                GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())),
+            );
+            lifetime
+        }
+    };
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(::scursor::Decode<#lifetime>));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let name = &input.ident;
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields)?;
+            let decode = fields.iter().map(|f| decode_field(f, &lifetime));
+            let value = construct(quote!(Self), &data.fields, &fields);
+            quote! {
+                #(#decode)*
+                ::core::result::Result::Ok(#value)
+            }
+        }
+        Data::Enum(_) => {
+            let (spec, variants) = parse_variants(input)?;
+            let unsigned = spec.unsigned();
+            let PrimitiveSpec { ty: tag_ty, order } = spec;
+            let arms = variants
+                .iter()
+                .map(|variant| {
+                    let fields = parse_fields(variant.fields)?;
+                    let decode = fields.iter().map(|f| decode_field(f, &lifetime));
+                    let ident = variant.ident;
+                    let tag = &variant.tag;
+                    let value = construct(quote!(Self::#ident), variant.fields, &fields);
+                    Ok(quote! {
+                        if __scursor_tag == (#tag) {
+                            #(#decode)*
+                            return ::core::result::Result::Ok(#value);
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            quote! {
+                let __scursor_position = cursor.position();
+                let __scursor_tag = cursor.read_primitive::<#tag_ty, #order>()?;
+                #(#arms)*
+                ::core::result::Result::Err(::scursor::ReadError::UnknownDiscriminant {
+                    position: __scursor_position,
+                    // widen through the unsigned type so that negative tags are not sign extended
+                    value: __scursor_tag as #unsigned as u64,
+                })
+            }
+        }
+        Data::Union(_) => unreachable!("rejected by check_container"),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::scursor::Decode<#lifetime> for #name #ty_generics #where_clause {
+            fn decode(
+                cursor: &mut ::scursor::ReadCursor<#lifetime>,
+            ) -> ::core::result::Result<Self, ::scursor::ReadError> {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_encode(input: &DeriveInput) -> Result<TokenStream2> {
+    check_container(input)?;
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::scursor::Encode));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let name = &input.ident;
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields)?;
+            let pattern = bind_fields(quote!(Self), &data.fields, &fields);
+            let encode = fields.iter().map(encode_field);
+            quote! {
+                let #pattern = self;
+                #(#encode)*
+            }
+        }
+        Data::Enum(_) => {
+            let (PrimitiveSpec { ty: tag_ty, order }, variants) = parse_variants(input)?;
+            let arms = variants
+                .iter()
+                .map(|variant| {
+                    let fields = parse_fields(variant.fields)?;
+                    let ident = variant.ident;
+                    let tag = &variant.tag;
+                    let pattern = bind_fields(quote!(Self::#ident), variant.fields, &fields);
+                    let encode = fields.iter().map(encode_field);
+                    Ok(quote! {
+                        #pattern => {
+                            ::scursor::Writer::write_primitive::<#tag_ty, #order>(cursor, #tag)?;
+                            #(#encode)*
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if arms.is_empty() {
+                quote!(match *self {})
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+        Data::Union(_) => unreachable!("rejected by check_container"),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::scursor::Encode for #name #ty_generics #where_clause {
+            fn encode<__ScursorWriter: ::scursor::Writer>(
+                &self,
+                cursor: &mut __ScursorWriter,
+            ) -> ::core::result::Result<(), ::scursor::WriteError> {
+                #body
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
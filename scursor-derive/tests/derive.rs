@@ -0,0 +1,218 @@
+//! Tests for the Decode and Encode derive macros
+
+use scursor::{Be, Decode, Encode, Le, ReadCursor, ReadError, WriteCursor};
+
+const MAGIC: u16 = 0x0564;
+
+#[derive(Debug, PartialEq, Decode, Encode)]
+struct Header {
+    #[scursor(be, magic = MAGIC)]
+    start: u16,
+    #[scursor(le)]
+    length: u16,
+    control: u8,
+    destination: Le<u16>,
+}
+
+#[derive(Debug, PartialEq, Decode, Encode)]
+struct Record<'a> {
+    id: Be<u32>,
+    #[scursor(prefix = u8)]
+    name: &'a [u8],
+    #[scursor(prefix = u16_be)]
+    header: Header,
+    flags: Option<u8>,
+}
+
+#[derive(Debug, PartialEq, Decode, Encode)]
+struct Pair(#[scursor(be)] i16, [u8; 2]);
+
+#[derive(Debug, PartialEq, Decode, Encode)]
+struct Empty;
+
+#[derive(Debug, PartialEq, Decode, Encode)]
+#[scursor(tag = u8)]
+enum Request<'a> {
+    #[scursor(tag = 0x01)]
+    Read {
+        #[scursor(be)]
+        start: u16,
+        #[scursor(be)]
+        count: u16,
+    },
+    #[scursor(tag = 0x02)]
+    Write(#[scursor(prefix = u8)] &'a [u8]),
+    #[scursor(tag = 0x03)]
+    Reset,
+}
+
+#[derive(Debug, PartialEq, Decode, Encode)]
+#[scursor(tag = u16_le)]
+enum Code {
+    Low = 0x0001,
+    High = 0x0100,
+}
+
+#[derive(Debug, PartialEq, Decode, Encode)]
+#[scursor(tag = i8)]
+enum Offset {
+    Back = -1,
+    Forward = 1,
+}
+
+#[derive(Debug, PartialEq, Decode, Encode)]
+struct Wrapper<T> {
+    value: T,
+}
+
+fn round_trip<'a, T>(value: &T, buffer: &'a mut [u8]) -> &'a [u8]
+where
+    T: Encode + Decode<'a> + PartialEq + core::fmt::Debug,
+{
+    let mut cursor = WriteCursor::new(buffer);
+    cursor.write(value).unwrap();
+    let length = cursor.position();
+    let bytes = &buffer[..length];
+    let mut reader = ReadCursor::new(bytes);
+    assert_eq!(&reader.read::<T>().unwrap(), value);
+    assert!(reader.is_empty());
+    bytes
+}
+
+#[test]
+fn struct_fields_use_attributes() {
+    let header = Header {
+        start: MAGIC,
+        length: 0x0102,
+        control: 0xC4,
+        destination: Le(0x0400),
+    };
+    let mut buffer = [0u8; 16];
+    assert_eq!(
+        round_trip(&header, &mut buffer),
+        &[0x05, 0x64, 0x02, 0x01, 0xC4, 0x00, 0x04]
+    );
+}
+
+#[test]
+fn magic_is_validated_and_always_written() {
+    let mut cursor = ReadCursor::new(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(
+        cursor.read::<Header>(),
+        Err(ReadError::BadMagic { position: 0 })
+    );
+
+    let header = Header {
+        start: 0,
+        length: 0,
+        control: 0,
+        destination: Le(0),
+    };
+    let mut buffer = [0u8; 7];
+    let mut cursor = WriteCursor::new(&mut buffer);
+    cursor.write(&header).unwrap();
+    assert_eq!(&cursor.written()[0..2], &[0x05, 0x64]);
+}
+
+#[test]
+fn length_prefixed_fields_round_trip() {
+    let record = Record {
+        id: Be(7),
+        name: b"pump",
+        header: Header {
+            start: MAGIC,
+            length: 1,
+            control: 2,
+            destination: Le(3),
+        },
+        flags: Some(0x80),
+    };
+    let mut buffer = [0u8; 32];
+    let bytes = round_trip(&record, &mut buffer);
+    assert_eq!(
+        &bytes[0..9],
+        &[0x00, 0x00, 0x00, 0x07, 0x04, b'p', b'u', b'm', b'p']
+    );
+    assert_eq!(&bytes[9..11], &[0x00, 0x07]);
+}
+
+#[test]
+fn unconsumed_bytes_in_prefixed_section_are_rejected() {
+    // id and an empty name followed by a header section with a trailing byte
+    let mut cursor = ReadCursor::new(&[
+        0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x08, 0x05, 0x64, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF,
+    ]);
+    assert_eq!(
+        cursor.read::<Record>(),
+        Err(ReadError::UnconsumedBytes {
+            position: 14,
+            count: 1
+        })
+    );
+    assert_eq!(cursor.position(), 0);
+}
+
+#[test]
+fn tuple_and_unit_structs_round_trip() {
+    let mut buffer = [0u8; 8];
+    assert_eq!(
+        round_trip(&Pair(-2, [0xAA, 0xBB]), &mut buffer),
+        &[0xFF, 0xFE, 0xAA, 0xBB]
+    );
+    assert_eq!(round_trip(&Empty, &mut buffer), &[]);
+}
+
+#[test]
+fn enum_variants_round_trip() {
+    let mut buffer = [0u8; 8];
+    assert_eq!(
+        round_trip(&Request::Read { start: 1, count: 2 }, &mut buffer),
+        &[0x01, 0x00, 0x01, 0x00, 0x02]
+    );
+    assert_eq!(
+        round_trip(&Request::Write(&[0xCA, 0xFE]), &mut buffer),
+        &[0x02, 0x02, 0xCA, 0xFE]
+    );
+    assert_eq!(round_trip(&Request::Reset, &mut buffer), &[0x03]);
+    assert_eq!(round_trip(&Code::High, &mut buffer), &[0x00, 0x01]);
+}
+
+#[test]
+fn unknown_discriminant_is_rejected() {
+    let mut cursor = ReadCursor::new(&[0xFF, 0x00, 0x04]);
+    cursor.read_u8().unwrap();
+    assert_eq!(
+        cursor.read::<Code>(),
+        Err(ReadError::UnknownDiscriminant {
+            position: 1,
+            value: 0x0400
+        })
+    );
+}
+
+#[test]
+fn unknown_signed_discriminant_is_not_sign_extended() {
+    let mut buffer = [0u8; 1];
+    assert_eq!(round_trip(&Offset::Back, &mut buffer), &[0xFF]);
+    assert_eq!(
+        ReadCursor::new(&[0xFE]).read::<Offset>(),
+        Err(ReadError::UnknownDiscriminant {
+            position: 0,
+            value: 0xFE
+        })
+    );
+}
+
+#[test]
+fn generic_structs_round_trip() {
+    let mut buffer = [0u8; 4];
+    assert_eq!(
+        round_trip(
+            &Wrapper {
+                value: Be(0xCAFEu16)
+            },
+            &mut buffer
+        ),
+        &[0xCA, 0xFE]
+    );
+}
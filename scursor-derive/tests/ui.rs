@@ -0,0 +1,7 @@
+//! Tests that invalid uses of the derive macros fail with the expected diagnostics
+
+#[test]
+fn invalid_derives_are_rejected() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+use scursor::Decode;
+
+#[derive(Decode)]
+struct Message<'a> {
+    payload: &'a [u8],
+    crc: u8,
+}
+
+fn main() {}
@@ -0,0 +1,412 @@
+use crate::{ReadCursor, ReadError, WriteCursor, WriteError};
+
+/// Order in which bits are packed into each byte
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The most significant bit of each byte comes first and multi-bit fields are
+    /// assembled most significant bit first
+    MsbFirst,
+    /// The least significant bit of each byte comes first and multi-bit fields are
+    /// assembled least significant bit first
+    LsbFirst,
+}
+
+/// Secure bit-level reader built on top of a [`ReadCursor`]
+#[derive(Copy, Clone, Debug)]
+pub struct BitReader<'a> {
+    cursor: ReadCursor<'a>,
+    // number of bits already consumed from the byte at the position of the cursor
+    bit: u8,
+    order: BitOrder,
+}
+
+impl<'a> BitReader<'a> {
+    /// Construct a bit reader starting at the current position of a cursor
+    pub fn new(cursor: ReadCursor<'a>, order: BitOrder) -> Self {
+        Self {
+            cursor,
+            bit: 0,
+            order,
+        }
+    }
+
+    /// Return the position of the reader in bits within the original input slice
+    pub fn bit_position(&self) -> usize {
+        self.cursor
+            .position()
+            .saturating_mul(8)
+            .saturating_add(self.bit as usize)
+    }
+
+    /// Return the number of bits remaining to be read
+    pub fn remaining_bits(&self) -> usize {
+        self.cursor
+            .remaining()
+            .saturating_mul(8)
+            .saturating_sub(self.bit as usize)
+    }
+
+    /// Return true if the reader is positioned on a byte boundary
+    pub fn is_byte_aligned(&self) -> bool {
+        self.bit == 0
+    }
+
+    /// Read a single bit
+    pub fn read_bit(&mut self) -> Result<bool, ReadError> {
+        let byte = self.cursor.peek_u8()?;
+        let shift = match self.order {
+            BitOrder::MsbFirst => 7 - self.bit,
+            BitOrder::LsbFirst => self.bit,
+        };
+        if self.bit == 7 {
+            self.cursor.read_u8()?;
+            self.bit = 0;
+        } else {
+            self.bit += 1;
+        }
+        Ok((byte >> shift) & 0x01 != 0)
+    }
+
+    /// Read a field of `count` bits, where `count` is in the range 1..=64
+    ///
+    /// The reader is only advanced if the entire field can be read
+    pub fn read_bits(&mut self, count: u32) -> Result<u64, ReadError> {
+        if count == 0 || count > 64 {
+            return Err(ReadError::BadBitCount { count });
+        }
+
+        if self.remaining_bits() < count as usize {
+            let requested = (self.bit as usize + count as usize).div_ceil(8);
+            return Err(self.cursor.insufficient(requested));
+        }
+
+        let mut reader = *self;
+        let mut value: u64 = 0;
+        for index in 0..count {
+            let bit = reader.read_bit()? as u64;
+            value = match self.order {
+                BitOrder::MsbFirst => (value << 1) | bit,
+                BitOrder::LsbFirst => value | (bit << index),
+            };
+        }
+        *self = reader;
+        Ok(value)
+    }
+
+    /// Convert the reader back into a byte cursor, discarding any unread bits in
+    /// a partially consumed byte so that the cursor is positioned on the next byte boundary
+    pub fn into_cursor(mut self) -> ReadCursor<'a> {
+        if self.bit != 0 {
+            // a partially consumed byte always exists in the underlying cursor
+            let _ = self.cursor.read_u8();
+        }
+        self.cursor
+    }
+}
+
+/// Secure bit-level writer built on top of a [`WriteCursor`]
+///
+/// Bits are accumulated until a byte is complete and then written to the cursor.
+/// A partially filled byte is only written when [`BitWriter::finish`] is called.
+///
+/// **[`BitWriter::finish`] must be called.** Dropping the writer silently discards
+/// any bits of an unflushed partial byte.
+#[derive(Debug)]
+#[must_use = "call finish() to write any partially filled byte"]
+pub struct BitWriter<'c, 'a> {
+    cursor: &'c mut WriteCursor<'a>,
+    // bits accumulated for the byte that has not yet been written
+    current: u8,
+    // number of bits accumulated in the current byte
+    bits: u8,
+    order: BitOrder,
+}
+
+impl<'c, 'a> BitWriter<'c, 'a> {
+    /// Construct a bit writer starting at the current position of a cursor
+    #[must_use = "call finish() to write any partially filled byte"]
+    pub fn new(cursor: &'c mut WriteCursor<'a>, order: BitOrder) -> Self {
+        Self {
+            cursor,
+            current: 0,
+            bits: 0,
+            order,
+        }
+    }
+
+    /// Return the position of the writer in bits within the underlying slice
+    pub fn bit_position(&self) -> usize {
+        self.cursor
+            .position()
+            .saturating_mul(8)
+            .saturating_add(self.bits as usize)
+    }
+
+    /// Return true if the writer is positioned on a byte boundary
+    pub fn is_byte_aligned(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Write a single bit
+    pub fn write_bit(&mut self, bit: bool) -> Result<(), WriteError> {
+        let shift = match self.order {
+            BitOrder::MsbFirst => 7 - self.bits,
+            BitOrder::LsbFirst => self.bits,
+        };
+        let current = self.current | ((bit as u8) << shift);
+        if self.bits == 7 {
+            self.cursor.write_u8(current)?;
+            self.current = 0;
+            self.bits = 0;
+        } else {
+            self.current = current;
+            self.bits += 1;
+        }
+        Ok(())
+    }
+
+    /// Write the lower `count` bits of `value`, where `count` is in the range 1..=64
+    ///
+    /// Values that do not fit in `count` bits are rejected. The writer is only
+    /// advanced if the entire field can be written.
+    pub fn write_bits(&mut self, value: u64, count: u32) -> Result<(), WriteError> {
+        if count == 0 || count > 64 {
+            return Err(WriteError::BadBitCount { count });
+        }
+        if count < 64 && value >> count != 0 {
+            return Err(WriteError::BitFieldOverflow { value, count });
+        }
+
+        let order = self.order;
+        self.transaction(|writer| {
+            for index in 0..count {
+                let shift = match order {
+                    BitOrder::MsbFirst => count - 1 - index,
+                    BitOrder::LsbFirst => index,
+                };
+                writer.write_bit((value >> shift) & 0x01 != 0)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Perform a transaction on the writer, restoring both the position of the
+    /// underlying cursor and any partially written byte if an error occurs
+    pub fn transaction<T, R>(&mut self, write: T) -> Result<R, WriteError>
+    where
+        T: FnOnce(&mut BitWriter) -> Result<R, WriteError>,
+    {
+        let start = self.cursor.position();
+        let current = self.current;
+        let bits = self.bits;
+        let result = write(self);
+        // if an error occurs, rollback to the starting position and partial byte
+        if result.is_err() {
+            self.cursor.seek_to(start)?;
+            self.current = current;
+            self.bits = bits;
+        }
+        result
+    }
+
+    /// Pad any partially written byte to the next byte boundary using the
+    /// `fill` bit and write it to the underlying cursor
+    ///
+    /// This must be called once all of the bits have been written
+    pub fn finish(mut self, fill: bool) -> Result<(), WriteError> {
+        while self.bits != 0 {
+            self.write_bit(fill)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_msb_first() {
+        let mut reader = BitReader::new(ReadCursor::new(&[0b1011_0010, 0xFF]), BitOrder::MsbFirst);
+        assert!(reader.read_bit().unwrap());
+        assert_eq!(reader.read_bits(3).unwrap(), 0b011);
+        assert_eq!(reader.bit_position(), 4);
+        assert_eq!(reader.read_bits(6).unwrap(), 0b00_1011);
+        assert_eq!(reader.bit_position(), 10);
+        assert_eq!(reader.remaining_bits(), 6);
+    }
+
+    #[test]
+    fn reads_lsb_first() {
+        let mut reader = BitReader::new(ReadCursor::new(&[0b1011_0010, 0x01]), BitOrder::LsbFirst);
+        assert!(!reader.read_bit().unwrap());
+        assert_eq!(reader.read_bits(2).unwrap(), 0b01);
+        // remaining 5 bits of the first byte followed by the low bit of the second
+        assert_eq!(reader.read_bits(6).unwrap(), 0b11_0110);
+        assert_eq!(reader.bit_position(), 9);
+    }
+
+    #[test]
+    fn reads_dnp3_double_bits() {
+        // four 2-bit values packed LSB first: 0b11, 0b10, 0b01, 0b00
+        let mut reader = BitReader::new(ReadCursor::new(&[0b00_01_10_11]), BitOrder::LsbFirst);
+        for expected in [0b11, 0b10, 0b01, 0b00] {
+            assert_eq!(reader.read_bits(2).unwrap(), expected);
+        }
+        assert_eq!(reader.remaining_bits(), 0);
+    }
+
+    #[test]
+    fn reads_full_64_bit_fields() {
+        let bytes = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF];
+        let mut reader = BitReader::new(ReadCursor::new(&bytes), BitOrder::MsbFirst);
+        assert_eq!(reader.read_bits(64).unwrap(), 0x0123456789ABCDEF);
+        let mut reader = BitReader::new(ReadCursor::new(&bytes), BitOrder::LsbFirst);
+        assert_eq!(reader.read_bits(64).unwrap(), 0xEFCDAB8967452301);
+    }
+
+    #[test]
+    fn rejects_invalid_bit_counts() {
+        let mut reader = BitReader::new(ReadCursor::new(&[0xFF; 16]), BitOrder::MsbFirst);
+        assert_eq!(
+            reader.read_bits(0),
+            Err(ReadError::BadBitCount { count: 0 })
+        );
+        assert_eq!(
+            reader.read_bits(65),
+            Err(ReadError::BadBitCount { count: 65 })
+        );
+        assert_eq!(reader.bit_position(), 0);
+    }
+
+    #[test]
+    fn insufficient_bits_does_not_advance() {
+        let mut cursor = ReadCursor::new(&[0xAA, 0xFF, 0xFF]);
+        cursor.read_u8().unwrap();
+        let mut reader = BitReader::new(cursor, BitOrder::MsbFirst);
+        reader.read_bits(3).unwrap();
+        assert_eq!(
+            reader.read_bits(14),
+            Err(ReadError::InsufficientBytes {
+                position: 1,
+                requested: 3,
+                remaining: 2
+            })
+        );
+        assert_eq!(reader.bit_position(), 11);
+        assert_eq!(reader.read_bits(13).unwrap(), 0x1FFF);
+        assert!(reader.read_bit().is_err());
+    }
+
+    #[test]
+    fn into_cursor_skips_to_next_byte_boundary() {
+        let mut reader = BitReader::new(ReadCursor::new(&[0xFF, 0xCA, 0xFE]), BitOrder::MsbFirst);
+        reader.read_bits(3).unwrap();
+        let mut cursor = reader.into_cursor();
+        assert_eq!(cursor.position(), 1);
+        assert_eq!(cursor.read_u16_be().unwrap(), 0xCAFE);
+
+        let reader = BitReader::new(ReadCursor::new(&[0xFF]), BitOrder::MsbFirst);
+        assert!(reader.is_byte_aligned());
+        assert_eq!(reader.into_cursor().position(), 0);
+    }
+
+    #[test]
+    fn writes_msb_first() {
+        let mut buffer = [0u8; 2];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        let mut writer = BitWriter::new(&mut cursor, BitOrder::MsbFirst);
+        writer.write_bit(true).unwrap();
+        writer.write_bits(0b011, 3).unwrap();
+        writer.write_bits(0b00_1011, 6).unwrap();
+        assert_eq!(writer.bit_position(), 10);
+        writer.finish(true).unwrap();
+        assert_eq!(cursor.written(), &[0b1011_0010, 0b1111_1111]);
+    }
+
+    #[test]
+    fn writes_dnp3_double_bits_lsb_first() {
+        let mut buffer = [0u8; 2];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        let mut writer = BitWriter::new(&mut cursor, BitOrder::LsbFirst);
+        for value in [0b11, 0b10, 0b01, 0b00, 0b01] {
+            writer.write_bits(value, 2).unwrap();
+        }
+        writer.finish(false).unwrap();
+        assert_eq!(cursor.written(), &[0b00_01_10_11, 0b0000_0001]);
+    }
+
+    #[test]
+    fn bit_writer_round_trips_with_reader() {
+        let fields: [(u64, u32); 5] = [(1, 1), (0x1F, 5), (u64::MAX, 64), (0, 7), (0x2AA, 10)];
+        for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let mut buffer = [0u8; 11];
+            let mut cursor = WriteCursor::new(&mut buffer);
+            let mut writer = BitWriter::new(&mut cursor, order);
+            for (value, count) in fields {
+                writer.write_bits(value, count).unwrap();
+            }
+            writer.finish(false).unwrap();
+
+            let mut reader = BitReader::new(ReadCursor::new(cursor.written()), order);
+            for (value, count) in fields {
+                assert_eq!(reader.read_bits(count).unwrap(), value);
+            }
+            assert_eq!(reader.remaining_bits(), 1);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_fields() {
+        let mut buffer = [0u8; 2];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        let mut writer = BitWriter::new(&mut cursor, BitOrder::MsbFirst);
+        assert_eq!(
+            writer.write_bits(0, 65),
+            Err(WriteError::BadBitCount { count: 65 })
+        );
+        assert_eq!(
+            writer.write_bits(0b100, 2),
+            Err(WriteError::BitFieldOverflow {
+                value: 0b100,
+                count: 2
+            })
+        );
+        assert_eq!(writer.bit_position(), 0);
+    }
+
+    #[test]
+    fn failed_write_restores_partial_byte() {
+        let mut buffer = [0u8; 1];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        let mut writer = BitWriter::new(&mut cursor, BitOrder::MsbFirst);
+        writer.write_bits(0b101, 3).unwrap();
+        assert_eq!(
+            writer.write_bits(0x1FFF, 13),
+            Err(WriteError::WriteOverflow {
+                remaining: 0,
+                written: 1
+            })
+        );
+        assert_eq!(writer.bit_position(), 3);
+        writer.write_bits(0b01, 2).unwrap();
+        writer.finish(false).unwrap();
+        assert_eq!(cursor.written(), &[0b1010_1000]);
+    }
+
+    #[test]
+    fn transaction_rolls_back_partial_byte() {
+        let mut buffer = [0u8; 1];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        let mut writer = BitWriter::new(&mut cursor, BitOrder::LsbFirst);
+        writer.write_bits(0b1, 1).unwrap();
+        let result = writer.transaction(|writer| {
+            writer.write_bits(0xFF, 8)?;
+            writer.write_bits(0, 8)
+        });
+        assert!(result.is_err());
+        assert_eq!(writer.bit_position(), 1);
+        writer.finish(false).unwrap();
+        assert_eq!(cursor.written(), &[0b0000_0001]);
+    }
+}
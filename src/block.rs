@@ -0,0 +1,288 @@
+use core::marker::PhantomData;
+use core::num::NonZeroUsize;
+
+use crate::{
+    ByteOrder, Checksum, Primitive, ReadCursor, ReadError, WriteCursor, WriteError, Writer,
+};
+
+/// Writer that inserts a checksum after every block of data, e.g. the CRC after each
+/// 16 bytes of user data in a DNP3 link frame
+///
+/// Each checksum of type `C` covers the data of its block and is written in byte order
+/// `E`. Data is written using the [`Writer`] routines, so any [`Encode`](crate::Encode)
+/// type can be written transparently. Writes only succeed if there is also room for the
+/// checksum of a trailing partial block, so finishing cannot fail after a successful write.
+///
+/// **[`BlockWriter::finish`] must be called.** Dropping a writer whose final block is
+/// partially filled leaves a frame that ends without its trailing checksum, which the
+/// receiver will reject.
+///
+/// ```
+/// use core::num::NonZeroUsize;
+/// use scursor::{BlockWriter, Crc16Dnp, LittleEndian, WriteCursor, Writer};
+///
+/// let mut buffer = [0u8; 24];
+/// let mut cursor = WriteCursor::new(&mut buffer);
+/// let block_size = NonZeroUsize::new(16).unwrap();
+/// let mut writer = BlockWriter::<Crc16Dnp, LittleEndian>::new(&mut cursor, block_size);
+/// writer.write_bytes(&[0xAA; 20]).unwrap();
+/// writer.finish().unwrap();
+/// assert_eq!(cursor.position(), 24);
+/// ```
+#[derive(Debug)]
+#[must_use = "call finish() to write the checksum of the final block"]
+pub struct BlockWriter<'c, 'a, C, E> {
+    cursor: &'c mut WriteCursor<'a>,
+    block_size: NonZeroUsize,
+    checksum: C,
+    // number of data bytes in the current block
+    filled: usize,
+    written: usize,
+    _order: PhantomData<E>,
+}
+
+impl<'c, 'a, C, E> BlockWriter<'c, 'a, C, E>
+where
+    C: Checksum,
+    E: ByteOrder,
+{
+    /// Construct a writer that writes blocks of `block_size` data bytes to a cursor
+    pub fn new(cursor: &'c mut WriteCursor<'a>, block_size: NonZeroUsize) -> Self {
+        Self {
+            cursor,
+            block_size,
+            checksum: C::new(),
+            filled: 0,
+            written: 0,
+            _order: PhantomData,
+        }
+    }
+
+    /// Write the checksum of the trailing partial block, if any
+    ///
+    /// This must be called once all of the data has been written
+    pub fn finish(mut self) -> Result<(), WriteError> {
+        if self.filled > 0 {
+            self.end_block()?;
+        }
+        Ok(())
+    }
+
+    fn checksum_size() -> usize {
+        core::mem::size_of::<<C::Output as Primitive>::Bytes>()
+    }
+
+    fn end_block(&mut self) -> Result<(), WriteError> {
+        let value = self.checksum.finish();
+        self.cursor.write_primitive::<C::Output, E>(value)?;
+        self.checksum = C::new();
+        self.filled = 0;
+        Ok(())
+    }
+}
+
+impl<C, E> Writer for BlockWriter<'_, '_, C, E>
+where
+    C: Checksum,
+    E: ByteOrder,
+{
+    /// Number of data bytes written, excluding checksums
+    fn position(&self) -> usize {
+        self.written
+    }
+
+    /// Number of data bytes that can be written while leaving room for every checksum,
+    /// including that of the final partial block
+    fn remaining(&self) -> usize {
+        let size = self.block_size.get();
+        let checksum = Self::checksum_size();
+        // total data in whole or partial blocks that fits, counting the current block
+        let space = self.cursor.remaining().saturating_add(self.filled);
+        let blocks = space / size.saturating_add(checksum);
+        let leftover = space % size.saturating_add(checksum);
+        let total = blocks
+            .saturating_mul(size)
+            .saturating_add(leftover.saturating_sub(checksum));
+        total.saturating_sub(self.filled)
+    }
+
+    /// Checksums are computed as data is written, so the writer can only "seek" to its
+    /// current position
+    fn seek_to(&mut self, pos: usize) -> Result<(), WriteError> {
+        if pos != self.written {
+            return Err(WriteError::BadSeek {
+                length: self.written,
+                pos,
+            });
+        }
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), WriteError> {
+        let size = self.block_size.get();
+        // reserve room for the checksum of every block touched, including a final partial one
+        let blocks = self.filled.saturating_add(bytes.len()).div_ceil(size);
+        let required = blocks
+            .checked_mul(Self::checksum_size())
+            .and_then(|x| x.checked_add(bytes.len()))
+            .ok_or(WriteError::NumericOverflow)?;
+        if self.cursor.remaining() < required {
+            return Err(WriteError::WriteOverflow {
+                remaining: self.remaining(),
+                written: bytes.len(),
+            });
+        }
+
+        let mut bytes = bytes;
+        while !bytes.is_empty() {
+            let count = (size - self.filled).min(bytes.len());
+            let (data, rest) = bytes.split_at(count);
+            self.cursor.write_bytes(data)?;
+            self.checksum.update(data);
+            self.filled += count;
+            self.written += count;
+            if self.filled == size {
+                self.end_block()?;
+            }
+            bytes = rest;
+        }
+        Ok(())
+    }
+}
+
+/// blocked checksum read routines
+impl ReadCursor<'_> {
+    /// Read `length` bytes of data divided into blocks of `block_size` bytes, each
+    /// followed by a checksum of type `C` in byte order `E`
+    ///
+    /// Every checksum is verified and the data is copied into `dest`, failing with
+    /// [`ReadError::ScratchTooSmall`] if it cannot hold it. The returned cursor reads
+    /// the data as a contiguous payload. This cursor is only advanced if all of the
+    /// blocks are valid.
+    ///
+    /// `dest` must hold `length` bytes even if the data fits in a single block, where it
+    /// would be contiguous in the input. Use [`ReadCursor::read_checked`] to borrow a
+    /// single block without copying it.
+    pub fn read_blocked<'b, C, E>(
+        &mut self,
+        block_size: NonZeroUsize,
+        length: usize,
+        dest: &'b mut [u8],
+    ) -> Result<ReadCursor<'b>, ReadError>
+    where
+        C: Checksum,
+        E: ByteOrder,
+    {
+        let capacity = dest.len();
+        let dest = dest.get_mut(0..length).ok_or(ReadError::ScratchTooSmall {
+            requested: length,
+            capacity,
+        })?;
+        let mut cursor = *self;
+        for chunk in dest.chunks_mut(block_size.get()) {
+            chunk.copy_from_slice(cursor.read_checked::<C, E>(chunk.len())?);
+        }
+        *self = cursor;
+        Ok(ReadCursor::new(dest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Crc16Dnp, LittleEndian, Sum8};
+
+    const SIXTEEN: NonZeroUsize = match NonZeroUsize::new(16) {
+        Some(x) => x,
+        None => unreachable!(),
+    };
+
+    fn payload() -> [u8; 20] {
+        let mut data = [0u8; 20];
+        for (i, x) in data.iter_mut().enumerate() {
+            *x = i as u8;
+        }
+        data
+    }
+
+    #[test]
+    fn blocks_round_trip() {
+        let data = payload();
+        let mut buffer = [0u8; 32];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        let mut writer = BlockWriter::<Crc16Dnp, LittleEndian>::new(&mut cursor, SIXTEEN);
+        writer.write_u8(data[0]).unwrap();
+        writer.write_bytes(&data[1..]).unwrap();
+        assert_eq!(writer.position(), 20);
+        writer.finish().unwrap();
+        assert_eq!(cursor.position(), 24);
+        assert_eq!(
+            &cursor.written()[16..18],
+            &Crc16Dnp::compute(&data[..16]).to_le_bytes()
+        );
+
+        let mut reader = ReadCursor::new(cursor.written());
+        let mut dest = [0u8; 32];
+        let mut payload = reader
+            .read_blocked::<Crc16Dnp, LittleEndian>(SIXTEEN, 20, &mut dest)
+            .unwrap();
+        assert_eq!(payload.read_all(), &data);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn remaining_accounts_for_checksums() {
+        let block_size = NonZeroUsize::new(4).unwrap();
+        let mut buffer = [0u8; 12];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        let mut writer = BlockWriter::<Sum8, LittleEndian>::new(&mut cursor, block_size);
+        writer.write_u8(0x01).unwrap();
+        // 3 more bytes, a checksum, 4 bytes, a checksum and 1 byte require 12 with the final checksum
+        assert_eq!(writer.remaining(), 8);
+        assert_eq!(
+            writer.write_bytes(&[0xFF; 9]),
+            Err(WriteError::WriteOverflow {
+                remaining: 8,
+                written: 9
+            })
+        );
+        writer.write_bytes(&[0xFF; 8]).unwrap();
+        assert_eq!(writer.remaining(), 0);
+        writer.finish().unwrap();
+        assert_eq!(cursor.remaining(), 0);
+    }
+
+    #[test]
+    fn dropping_without_finish_omits_final_checksum() {
+        let block_size = NonZeroUsize::new(4).unwrap();
+        let mut buffer = [0u8; 8];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        let mut writer = BlockWriter::<Sum8, LittleEndian>::new(&mut cursor, block_size);
+        writer.write_bytes(&[0x01, 0x02]).unwrap();
+        drop(writer);
+        assert_eq!(cursor.written(), &[0x01, 0x02]);
+
+        let mut writer = BlockWriter::<Sum8, LittleEndian>::new(&mut cursor, block_size);
+        writer.write_bytes(&[0x01, 0x02]).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(cursor.written(), &[0x01, 0x02, 0x01, 0x02, 0x03]);
+    }
+
+    #[test]
+    fn corrupted_block_is_rejected() {
+        let mut buffer = [0u8; 24];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        let mut writer = BlockWriter::<Crc16Dnp, LittleEndian>::new(&mut cursor, SIXTEEN);
+        writer.write_bytes(&payload()).unwrap();
+        writer.finish().unwrap();
+        buffer[19] ^= 0x01;
+
+        let mut reader = ReadCursor::new(&buffer);
+        let mut dest = [0u8; 20];
+        assert!(matches!(
+            reader.read_blocked::<Crc16Dnp, LittleEndian>(SIXTEEN, 20, &mut dest),
+            Err(ReadError::ChecksumMismatch { position: 22, .. })
+        ));
+        assert_eq!(reader.position(), 0);
+    }
+}
@@ -0,0 +1,390 @@
+use crate::read::impl_typed_readers;
+use crate::{ByteOrder, Primitive, ReadError, TrailingBytes};
+
+/// Secure read-only cursor over a chain of non-contiguous slices
+///
+/// Provides the primitive, LEB128 and length-prefixed read routines of
+/// [`ReadCursor`](crate::ReadCursor) for data that is split across several buffers, e.g.
+/// in scatter-gather I/O. Values may straddle the boundaries between segments. Positions
+/// are relative to the start of the first segment.
+///
+/// The API is deliberately narrower than that of `ReadCursor`: routines that borrow an
+/// arbitrary amount of contiguous input, such as `read_all` and the string readers, are
+/// not provided. Use [`ChainReadCursor::read_bytes`] with a scratch buffer instead.
+#[derive(Copy, Clone, Debug)]
+pub struct ChainReadCursor<'s, 'a> {
+    segments: &'s [&'a [u8]],
+    // index of the current segment and offset within it
+    index: usize,
+    offset: usize,
+    pos: usize,
+    len: usize,
+}
+
+impl<'s, 'a> ChainReadCursor<'s, 'a> {
+    /// Construct a cursor over a borrowed list of segments
+    pub fn new(segments: &'s [&'a [u8]]) -> Self {
+        let len = segments
+            .iter()
+            .fold(0usize, |sum, x| sum.saturating_add(x.len()));
+        let mut cursor = Self {
+            segments,
+            index: 0,
+            offset: 0,
+            pos: 0,
+            len,
+        };
+        cursor.skip_exhausted();
+        cursor
+    }
+
+    /// Read a single unsigned byte from the cursor
+    pub fn read_u8(&mut self) -> Result<u8, ReadError> {
+        let mut byte = [0u8];
+        self.read_into(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    /// Expect the cursor to be empty or return and error indicating how many trailing
+    /// bytes are present
+    pub fn expect_empty(&self) -> Result<(), TrailingBytes> {
+        match core::num::NonZeroUsize::new(self.remaining()) {
+            None => Ok(()),
+            Some(x) => Err(TrailingBytes { count: x }),
+        }
+    }
+
+    /// Return the number of bytes remaining to be read
+    pub fn remaining(&self) -> usize {
+        self.len.saturating_sub(self.pos)
+    }
+
+    /// Return the position of the cursor within the chain of segments
+    ///
+    /// This is synonymous with the number of bytes consumed by the cursor.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Perform a transaction on the cursor, returning it to its initial
+    /// state if an error occurs
+    pub fn transaction<T, R, E>(&mut self, read: T) -> Result<R, E>
+    where
+        T: FnOnce(&mut ChainReadCursor<'s, 'a>) -> Result<R, E>,
+    {
+        let mut copy = *self;
+        let value = read(&mut copy)?;
+        *self = copy;
+        Ok(value)
+    }
+
+    /// Return true if there are no more bytes remaining to be read
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Advance the cursor a count of bytes
+    pub fn skip(&mut self, count: usize) -> Result<(), ReadError> {
+        if self.remaining() < count {
+            return Err(self.insufficient(count));
+        }
+        self.advance(count);
+        Ok(())
+    }
+
+    /// Read a count of bytes, borrowing them if they lie within a single segment
+    ///
+    /// If the bytes straddle a segment boundary they are copied into `scratch`, which
+    /// fails with [`ReadError::ScratchTooSmall`] if it cannot hold them. The cursor is
+    /// only advanced if the bytes can be read.
+    ///
+    /// ```
+    /// use scursor::ChainReadCursor;
+    ///
+    /// let segments: [&[u8]; 2] = [&[0x01, 0x02, 0x03], &[0x04]];
+    /// let mut cursor = ChainReadCursor::new(&segments);
+    /// let mut scratch = [0u8; 4];
+    /// assert_eq!(cursor.read_bytes(2, &mut scratch).unwrap(), &[0x01, 0x02]);
+    /// assert_eq!(cursor.read_bytes(2, &mut scratch).unwrap(), &[0x03, 0x04]);
+    /// ```
+    pub fn read_bytes<'b>(
+        &mut self,
+        count: usize,
+        scratch: &'b mut [u8],
+    ) -> Result<&'b [u8], ReadError>
+    where
+        'a: 'b,
+    {
+        if let Some(bytes) = self.read_borrowed(count)? {
+            return Ok(bytes);
+        }
+        let capacity = scratch.len();
+        let dest = scratch
+            .get_mut(0..count)
+            .ok_or(ReadError::ScratchTooSmall {
+                requested: count,
+                capacity,
+            })?;
+        self.read_into(dest)?;
+        Ok(dest)
+    }
+
+    /// Read a count of bytes if they lie within a single segment, borrowing them for the
+    /// lifetime of the underlying data
+    ///
+    /// Returns `None` without advancing the cursor if the bytes straddle a segment
+    /// boundary, in which case they can be copied with [`ChainReadCursor::read_bytes`].
+    pub fn read_borrowed(&mut self, count: usize) -> Result<Option<&'a [u8]>, ReadError> {
+        if self.remaining() < count {
+            return Err(self.insufficient(count));
+        }
+        let bytes = self.current().get(0..count);
+        if bytes.is_some() {
+            self.advance(count);
+        }
+        Ok(bytes)
+    }
+
+    /// Read a count of bytes as a child cursor bounded to those bytes
+    ///
+    /// The parent cursor is advanced past the bytes. The child cursor reports positions
+    /// relative to the start of the first segment, like its parent.
+    pub fn sub_cursor(&mut self, count: usize) -> Result<ChainReadCursor<'s, 'a>, ReadError> {
+        if self.remaining() < count {
+            return Err(self.insufficient(count));
+        }
+        let mut child = *self;
+        child.len = self.pos + count;
+        self.advance(count);
+        Ok(child)
+    }
+
+    /// Read a length prefix of type `T` in byte order `E` and return a child cursor
+    /// bounded to the section of that length
+    ///
+    /// The cursor is only advanced if both the prefix and the section can be read.
+    pub fn read_length_prefixed<T, E>(&mut self) -> Result<ChainReadCursor<'s, 'a>, ReadError>
+    where
+        T: Primitive + TryInto<usize>,
+        E: ByteOrder,
+    {
+        let mut cursor = *self;
+        let position = cursor.position();
+        let length = cursor
+            .read_primitive::<T, E>()?
+            .try_into()
+            .map_err(|_| ReadError::BadLength { position })?;
+        let section = cursor.sub_cursor(length)?;
+        *self = cursor;
+        Ok(section)
+    }
+
+    /// Fill a slice with the next bytes, which may span several segments
+    ///
+    /// The cursor is only advanced if the entire slice can be filled
+    pub fn read_into(&mut self, dest: &mut [u8]) -> Result<(), ReadError> {
+        if self.remaining() < dest.len() {
+            return Err(self.insufficient(dest.len()));
+        }
+        let mut dest = dest;
+        while !dest.is_empty() {
+            let src = self.current();
+            let count = src.len().min(dest.len());
+            let (head, rest) = dest.split_at_mut(count);
+            if let Some(src) = src.get(0..count) {
+                head.copy_from_slice(src);
+            }
+            self.advance(count);
+            dest = rest;
+        }
+        Ok(())
+    }
+
+    /// Read a primitive value using the specified byte order
+    ///
+    /// The cursor is only advanced if the entire value can be read
+    pub fn read_primitive<T, E>(&mut self) -> Result<T, ReadError>
+    where
+        T: Primitive,
+        E: ByteOrder,
+    {
+        let mut bytes = T::Bytes::default();
+        self.read_into(bytes.as_mut())?;
+        Ok(E::decode(bytes))
+    }
+
+    /// Run a read operation against a copy of the cursor, returning its result
+    ///
+    /// The position of this cursor is never modified, regardless of whether the
+    /// operation succeeds or fails
+    pub fn peek<T, R, E>(&self, read: T) -> Result<R, E>
+    where
+        T: FnOnce(&mut ChainReadCursor<'s, 'a>) -> Result<R, E>,
+    {
+        let mut copy = *self;
+        read(&mut copy)
+    }
+
+    /// Peek a single unsigned byte without advancing the cursor
+    pub fn peek_u8(&self) -> Result<u8, ReadError> {
+        self.peek(|cur| cur.read_u8())
+    }
+
+    /// Peek a primitive value using the specified byte order without advancing the cursor
+    pub fn peek_primitive<T, E>(&self) -> Result<T, ReadError>
+    where
+        T: Primitive,
+        E: ByteOrder,
+    {
+        self.peek(|cur| cur.read_primitive::<T, E>())
+    }
+
+    /// Unread bytes of the current segment
+    fn current(&self) -> &'a [u8] {
+        self.segments
+            .get(self.index)
+            .and_then(|x| x.get(self.offset..))
+            .unwrap_or(&[])
+    }
+
+    /// Advance over bytes known to be available, moving past exhausted segments
+    fn advance(&mut self, count: usize) {
+        self.pos = self.pos.saturating_add(count);
+        self.offset = self.offset.saturating_add(count);
+        self.skip_exhausted();
+    }
+
+    /// Move to the first segment with unread bytes, skipping any that are empty
+    fn skip_exhausted(&mut self) {
+        while let Some(segment) = self.segments.get(self.index) {
+            if self.offset < segment.len() {
+                return;
+            }
+            self.offset -= segment.len();
+            self.index += 1;
+        }
+    }
+
+    fn insufficient(&self, requested: usize) -> ReadError {
+        ReadError::InsufficientBytes {
+            position: self.position(),
+            requested,
+            remaining: self.remaining(),
+        }
+    }
+}
+
+impl_typed_readers!(ChainReadCursor<'_, '_>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LittleEndian;
+
+    const SEGMENTS: [&[u8]; 4] = [&[0x01, 0xCA], &[], &[0xFE, 0xBA, 0xBE], &[0x02]];
+
+    #[test]
+    fn integers_straddle_segment_boundaries() {
+        let mut cursor = ChainReadCursor::new(&SEGMENTS);
+        assert_eq!(cursor.read_u8().unwrap(), 0x01);
+        assert_eq!(cursor.read_u16_be().unwrap(), 0xCAFE);
+        assert_eq!(cursor.peek_u8().unwrap(), 0xBA);
+        assert_eq!(cursor.read_u16_le().unwrap(), 0xBEBA);
+        assert_eq!(cursor.read_u8().unwrap(), 0x02);
+        assert!(cursor.is_empty());
+        assert!(cursor.expect_empty().is_ok());
+    }
+
+    #[test]
+    fn failed_reads_do_not_advance() {
+        let mut cursor = ChainReadCursor::new(&SEGMENTS);
+        cursor.skip(3).unwrap();
+        assert_eq!(
+            cursor.read_u32_be(),
+            Err(ReadError::InsufficientBytes {
+                position: 3,
+                requested: 4,
+                remaining: 3
+            })
+        );
+        assert_eq!(cursor.position(), 3);
+
+        let mut cursor = ChainReadCursor::new(&SEGMENTS);
+        cursor.skip(2).unwrap();
+        let result = cursor.transaction(|cur| {
+            cur.read_u16_be()?;
+            cur.read_u32_le()
+        });
+        assert_eq!(
+            result,
+            Err(ReadError::InsufficientBytes {
+                position: 4,
+                requested: 4,
+                remaining: 2
+            })
+        );
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.read_u32_be().unwrap(), 0xFEBABE02);
+    }
+
+    #[test]
+    fn empty_segments_are_skipped() {
+        let segments: [&[u8]; 5] = [&[], &[], &[0x01, 0x02], &[], &[0x03]];
+        let mut cursor = ChainReadCursor::new(&segments);
+        assert_eq!(cursor.read_bytes(1, &mut []).unwrap(), &[0x01]);
+        assert_eq!(cursor.read_bytes(1, &mut []).unwrap(), &[0x02]);
+        assert_eq!(cursor.read_bytes(1, &mut []).unwrap(), &[0x03]);
+        assert!(cursor.is_empty());
+    }
+
+    #[test]
+    fn borrowed_bytes_outlive_the_segment_list() {
+        let data = [0x01, 0x02, 0x03];
+        let borrowed = {
+            let segments = [&data[..2], &data[2..]];
+            let mut cursor = ChainReadCursor::new(&segments);
+            assert_eq!(cursor.read_borrowed(3), Ok(None));
+            cursor.read_borrowed(2).unwrap().unwrap()
+        };
+        assert_eq!(borrowed, &[0x01, 0x02]);
+    }
+
+    #[test]
+    fn reads_leb128_and_length_prefixed_sections() {
+        let segments: [&[u8]; 3] = [&[0xAC], &[0x02, 0x03, 0xAA], &[0xBB, 0xCC, 0x7F]];
+        let mut cursor = ChainReadCursor::new(&segments);
+        assert_eq!(cursor.read_uleb128_u64().unwrap(), 300);
+        let mut section = cursor.read_length_prefixed::<u8, LittleEndian>().unwrap();
+        assert_eq!(section.position(), 3);
+        assert_eq!(section.read_u16_be().unwrap(), 0xAABB);
+        assert_eq!(
+            section.read_u16_be(),
+            Err(ReadError::InsufficientBytes {
+                position: 5,
+                requested: 2,
+                remaining: 1
+            })
+        );
+        assert_eq!(section.read_u8().unwrap(), 0xCC);
+        assert!(section.is_empty());
+        assert_eq!(cursor.read_sleb128_i64().unwrap(), -1);
+        assert!(cursor.is_empty());
+    }
+
+    #[test]
+    fn read_bytes_borrows_or_copies() {
+        let mut cursor = ChainReadCursor::new(&SEGMENTS);
+        let mut scratch = [0u8; 2];
+        assert_eq!(cursor.read_bytes(1, &mut []).unwrap(), &[0x01]);
+        assert_eq!(
+            cursor.read_bytes(3, &mut scratch),
+            Err(ReadError::ScratchTooSmall {
+                requested: 3,
+                capacity: 2
+            })
+        );
+        assert_eq!(cursor.read_bytes(2, &mut scratch).unwrap(), &[0xCA, 0xFE]);
+        assert_eq!(cursor.read_bytes(2, &mut []).unwrap(), &[0xBA, 0xBE]);
+        assert_eq!(cursor.position(), 5);
+    }
+}
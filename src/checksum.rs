@@ -0,0 +1,343 @@
+use crate::{ByteOrder, Primitive, ReadCursor, ReadError, WriteCursor, WriteError};
+
+/// Incrementally computed checksum such as a CRC or a simple sum
+///
+/// ```
+/// use scursor::{Checksum, Crc16Dnp};
+///
+/// let mut crc = Crc16Dnp::new();
+/// crc.update(b"1234");
+/// crc.update(b"56789");
+/// assert_eq!(crc.finish(), Crc16Dnp::compute(b"123456789"));
+/// ```
+pub trait Checksum: Sized {
+    /// Value produced by the checksum
+    type Output: Primitive + Into<u64>;
+
+    /// Start a new computation
+    fn new() -> Self;
+
+    /// Add bytes to the computation
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Return the checksum of all of the bytes added so far
+    fn finish(&self) -> Self::Output;
+
+    /// Compute the checksum of a slice of bytes
+    fn compute(bytes: &[u8]) -> Self::Output {
+        let mut checksum = Self::new();
+        checksum.update(bytes);
+        checksum.finish()
+    }
+}
+
+/// CRC-16 used by DNP3 (polynomial 0x3D65, reflected, inverted output)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Crc16Dnp {
+    crc: u16,
+}
+
+/// CRC-16 used by Modbus RTU (polynomial 0x8005, reflected, initial value 0xFFFF)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Crc16Modbus {
+    crc: u16,
+}
+
+/// CRC-32 used by Ethernet, zip and many file formats (polynomial 0x04C11DB7, reflected)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Crc32 {
+    crc: u32,
+}
+
+/// Sum of all bytes modulo 256
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sum8 {
+    sum: u8,
+}
+
+/// Sum of all bytes modulo 65536
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sum16 {
+    sum: u16,
+}
+
+const fn reflected_table_16(poly: u16) -> [u16; 256] {
+    let mut table = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u16;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+const fn reflected_table_32(poly: u32) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+// tables use the bit-reversed form of each polynomial
+static CRC16_DNP_TABLE: [u16; 256] = reflected_table_16(0xA6BC);
+static CRC16_MODBUS_TABLE: [u16; 256] = reflected_table_16(0xA001);
+static CRC32_TABLE: [u32; 256] = reflected_table_32(0xEDB8_8320);
+
+fn update_reflected_16(table: &[u16; 256], crc: u16, bytes: &[u8]) -> u16 {
+    bytes.iter().fold(crc, |crc, byte| {
+        (crc >> 8) ^ table[usize::from(crc as u8 ^ byte)]
+    })
+}
+
+impl Checksum for Crc16Dnp {
+    type Output = u16;
+
+    fn new() -> Self {
+        Self { crc: 0 }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.crc = update_reflected_16(&CRC16_DNP_TABLE, self.crc, bytes);
+    }
+
+    fn finish(&self) -> u16 {
+        !self.crc
+    }
+}
+
+impl Checksum for Crc16Modbus {
+    type Output = u16;
+
+    fn new() -> Self {
+        Self { crc: 0xFFFF }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.crc = update_reflected_16(&CRC16_MODBUS_TABLE, self.crc, bytes);
+    }
+
+    fn finish(&self) -> u16 {
+        self.crc
+    }
+}
+
+impl Checksum for Crc32 {
+    type Output = u32;
+
+    fn new() -> Self {
+        Self { crc: 0xFFFF_FFFF }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.crc = bytes.iter().fold(self.crc, |crc, byte| {
+            (crc >> 8) ^ CRC32_TABLE[usize::from(crc as u8 ^ byte)]
+        });
+    }
+
+    fn finish(&self) -> u32 {
+        !self.crc
+    }
+}
+
+impl Checksum for Sum8 {
+    type Output = u8;
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.sum = bytes.iter().fold(self.sum, |sum, x| sum.wrapping_add(*x));
+    }
+
+    fn finish(&self) -> u8 {
+        self.sum
+    }
+}
+
+impl Checksum for Sum16 {
+    type Output = u16;
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.sum = bytes
+            .iter()
+            .fold(self.sum, |sum, x| sum.wrapping_add(u16::from(*x)));
+    }
+
+    fn finish(&self) -> u16 {
+        self.sum
+    }
+}
+
+/// checksum write routines
+impl WriteCursor<'_> {
+    /// Compute a checksum over the bytes written since position `since` and append it
+    /// using byte order `E`
+    ///
+    /// Fails with [`WriteError::BadRange`] if `since` is beyond the current position.
+    ///
+    /// ```
+    /// use scursor::{Crc16Modbus, LittleEndian, WriteCursor};
+    ///
+    /// let mut buffer = [0u8; 8];
+    /// let mut cursor = WriteCursor::new(&mut buffer);
+    /// cursor.write_bytes(&[0x01, 0x03, 0x00, 0x00, 0x00, 0x0A]).unwrap();
+    /// cursor.write_checksum::<Crc16Modbus, LittleEndian>(0).unwrap();
+    /// assert_eq!(&cursor.written()[6..], &[0xC5, 0xCD]);
+    /// ```
+    pub fn write_checksum<C, E>(&mut self, since: usize) -> Result<C::Output, WriteError>
+    where
+        C: Checksum,
+        E: ByteOrder,
+    {
+        let value = match self.get(since..self.position()) {
+            Some(bytes) => C::compute(bytes),
+            None => {
+                return Err(WriteError::BadRange {
+                    start: since,
+                    end: self.position(),
+                })
+            }
+        };
+        self.write_primitive::<C::Output, E>(value)?;
+        Ok(value)
+    }
+}
+
+/// checksum read routines
+impl<'a> ReadCursor<'a> {
+    /// Read a checksum in byte order `E` and verify it against the bytes consumed since
+    /// position `since`
+    ///
+    /// Positions are those returned by [`ReadCursor::position`]. The cursor is only
+    /// advanced if the checksum matches.
+    pub fn verify_checksum<C, E>(&mut self, since: usize) -> Result<(), ReadError>
+    where
+        C: Checksum,
+        E: ByteOrder,
+    {
+        let bytes = self.consumed_since(since)?;
+        let mut cursor = *self;
+        let position = cursor.position();
+        let received = cursor.read_primitive::<C::Output, E>()?;
+        let expected = C::compute(bytes);
+        if received.into() != expected.into() {
+            return Err(ReadError::ChecksumMismatch {
+                position,
+                expected: expected.into(),
+                received: received.into(),
+            });
+        }
+        *self = cursor;
+        Ok(())
+    }
+
+    /// Read a count of bytes followed by a checksum of them in byte order `E`
+    ///
+    /// The cursor is only advanced if the checksum matches.
+    pub fn read_checked<C, E>(&mut self, count: usize) -> Result<&'a [u8], ReadError>
+    where
+        C: Checksum,
+        E: ByteOrder,
+    {
+        let mut cursor = *self;
+        let start = cursor.position();
+        let bytes = cursor.read_bytes(count)?;
+        cursor.verify_checksum::<C, E>(start)?;
+        *self = cursor;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BigEndian, LittleEndian};
+
+    const CHECK: &[u8] = b"123456789";
+
+    #[test]
+    fn check_values_match_catalogue() {
+        assert_eq!(Crc16Dnp::compute(CHECK), 0xEA82);
+        assert_eq!(Crc16Modbus::compute(CHECK), 0x4B37);
+        assert_eq!(Crc32::compute(CHECK), 0xCBF4_3926);
+        assert_eq!(Sum8::compute(CHECK), 0xDD);
+        assert_eq!(Sum16::compute(CHECK), 0x01DD);
+    }
+
+    #[test]
+    fn dnp3_header_round_trips() {
+        let mut buffer = [0u8; 10];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor
+            .write_bytes(&[0x05, 0x64, 0x05, 0xC0, 0x01, 0x00, 0x00, 0x04])
+            .unwrap();
+        cursor.write_checksum::<Crc16Dnp, LittleEndian>(0).unwrap();
+        assert_eq!(&cursor.written()[8..], &[0xE9, 0x21]);
+
+        let mut reader = ReadCursor::new(cursor.written());
+        assert_eq!(
+            reader.read_checked::<Crc16Dnp, LittleEndian>(8).unwrap(),
+            &[0x05, 0x64, 0x05, 0xC0, 0x01, 0x00, 0x00, 0x04]
+        );
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn mismatch_does_not_advance() {
+        let mut cursor = ReadCursor::new(&[0x01, 0x02, 0x00, 0x04]);
+        cursor.read_bytes(2).unwrap();
+        assert_eq!(
+            cursor.verify_checksum::<Sum16, BigEndian>(0),
+            Err(ReadError::ChecksumMismatch {
+                position: 2,
+                expected: 0x0003,
+                received: 0x0004
+            })
+        );
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(
+            cursor.verify_checksum::<Sum16, BigEndian>(3),
+            Err(ReadError::BadRange { start: 3, end: 2 })
+        );
+    }
+
+    #[test]
+    fn write_checksum_rejects_future_position() {
+        let mut buffer = [0u8; 4];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor.write_u8(0x01).unwrap();
+        assert_eq!(
+            cursor.write_checksum::<Sum8, LittleEndian>(2),
+            Err(WriteError::BadRange { start: 2, end: 1 })
+        );
+        assert_eq!(cursor.write_checksum::<Sum8, LittleEndian>(0), Ok(0x01));
+    }
+}
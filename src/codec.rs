@@ -0,0 +1,313 @@
+use crate::{
+    BigEndian, LittleEndian, Primitive, ReadCursor, ReadError, SizeCursor, WriteCursor, WriteError,
+    Writer,
+};
+
+/// Type that can be decoded from a [`ReadCursor`]
+///
+/// Implementations may leave the cursor partially advanced on error. Use
+/// [`ReadCursor::read`] to decode values transactionally.
+pub trait Decode<'a>: Sized {
+    /// Decode a value from the cursor
+    fn decode(cursor: &mut ReadCursor<'a>) -> Result<Self, ReadError>;
+}
+
+/// Type that can be encoded into any [`Writer`]
+///
+/// Implementations may leave the writer partially advanced on error. Use
+/// [`WriteCursor::write`] to encode values transactionally.
+pub trait Encode {
+    /// Encode the value into the writer
+    fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError>;
+
+    /// Compute the number of bytes produced by [`Encode::encode`] without writing them
+    fn encoded_len(&self) -> Result<usize, WriteError> {
+        let mut size = SizeCursor::new();
+        self.encode(&mut size)?;
+        Ok(size.len())
+    }
+}
+
+/// Wrapper that encodes and decodes a primitive in little-endian byte order
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Le<T>(pub T);
+
+/// Wrapper that encodes and decodes a primitive in big-endian byte order
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Be<T>(pub T);
+
+impl<'a> ReadCursor<'a> {
+    /// Decode a value from the cursor, leaving the cursor unmodified if an error occurs
+    pub fn read<T: Decode<'a>>(&mut self) -> Result<T, ReadError> {
+        let mut cursor = *self;
+        let value = T::decode(&mut cursor)?;
+        *self = cursor;
+        Ok(value)
+    }
+}
+
+impl<'a> WriteCursor<'a> {
+    /// Encode a value into the cursor, rolling back the position if an error occurs
+    pub fn write<T: Encode + ?Sized>(&mut self, value: &T) -> Result<(), WriteError> {
+        self.transaction(|cur| value.encode(cur))
+    }
+}
+
+impl<'a> Decode<'a> for u8 {
+    fn decode(cursor: &mut ReadCursor<'a>) -> Result<Self, ReadError> {
+        cursor.read_u8()
+    }
+}
+
+impl Encode for u8 {
+    fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError> {
+        writer.write_u8(*self)
+    }
+}
+
+impl<'a> Decode<'a> for i8 {
+    fn decode(cursor: &mut ReadCursor<'a>) -> Result<Self, ReadError> {
+        cursor.read_primitive::<i8, LittleEndian>()
+    }
+}
+
+impl Encode for i8 {
+    fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError> {
+        writer.write_primitive::<i8, LittleEndian>(*self)
+    }
+}
+
+impl<'a, T: Primitive> Decode<'a> for Le<T> {
+    fn decode(cursor: &mut ReadCursor<'a>) -> Result<Self, ReadError> {
+        cursor.read_primitive::<T, LittleEndian>().map(Le)
+    }
+}
+
+impl<T: Primitive> Encode for Le<T> {
+    fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError> {
+        writer.write_primitive::<T, LittleEndian>(self.0)
+    }
+}
+
+impl<'a, T: Primitive> Decode<'a> for Be<T> {
+    fn decode(cursor: &mut ReadCursor<'a>) -> Result<Self, ReadError> {
+        cursor.read_primitive::<T, BigEndian>().map(Be)
+    }
+}
+
+impl<T: Primitive> Encode for Be<T> {
+    fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError> {
+        writer.write_primitive::<T, BigEndian>(self.0)
+    }
+}
+
+impl<'a, T, const N: usize> Decode<'a> for [T; N]
+where
+    T: Decode<'a> + Copy + Default,
+{
+    fn decode(cursor: &mut ReadCursor<'a>) -> Result<Self, ReadError> {
+        let mut values = [T::default(); N];
+        for value in values.iter_mut() {
+            *value = T::decode(cursor)?;
+        }
+        Ok(values)
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError> {
+        for value in self.iter() {
+            value.encode(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// A borrowed slice is decoded from all of the remaining bytes in the cursor
+///
+/// It should therefore only be used as the last value of a message or within a
+/// length-prefixed section.
+impl<'a> Decode<'a> for &'a [u8] {
+    fn decode(cursor: &mut ReadCursor<'a>) -> Result<Self, ReadError> {
+        Ok(cursor.read_all())
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError> {
+        for value in self.iter() {
+            value.encode(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// An optional value is preceded by a presence byte which is 0x00 if absent or 0x01 if present
+impl<'a, T: Decode<'a>> Decode<'a> for Option<T> {
+    fn decode(cursor: &mut ReadCursor<'a>) -> Result<Self, ReadError> {
+        let position = cursor.position();
+        match cursor.read_u8()? {
+            0x00 => Ok(None),
+            0x01 => T::decode(cursor).map(Some),
+            x => Err(ReadError::UnknownDiscriminant {
+                position,
+                value: x.into(),
+            }),
+        }
+    }
+}
+
+/// An optional value is preceded by a presence byte which is 0x00 if absent or 0x01 if present
+impl<T: Encode> Encode for Option<T> {
+    fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError> {
+        match self {
+            Some(x) => {
+                writer.write_u8(0x01)?;
+                x.encode(writer)
+            }
+            None => writer.write_u8(0x00),
+        }
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError> {
+        (**self).encode(writer)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+        impl<'a, $($name: Decode<'a>),+> Decode<'a> for ($($name,)+) {
+            fn decode(cursor: &mut ReadCursor<'a>) -> Result<Self, ReadError> {
+                Ok(($($name::decode(cursor)?,)+))
+            }
+        }
+
+        impl<$($name: Encode),+> Encode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError> {
+                let ($($name,)+) = self;
+                $($name.encode(writer)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+impl_tuple!(A, B, C, D, E, F, G);
+impl_tuple!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Header<'a> {
+        function: u8,
+        length: Be<u16>,
+        values: [Le<i16>; 2],
+        payload: &'a [u8],
+    }
+
+    impl<'a> Decode<'a> for Header<'a> {
+        fn decode(cursor: &mut ReadCursor<'a>) -> Result<Self, ReadError> {
+            let function = cursor.read()?;
+            let length: Be<u16> = cursor.read()?;
+            let values = cursor.read()?;
+            let payload = cursor.read_bytes(length.0 as usize)?;
+            Ok(Self {
+                function,
+                length,
+                values,
+                payload,
+            })
+        }
+    }
+
+    impl Encode for Header<'_> {
+        fn encode<W: Writer>(&self, writer: &mut W) -> Result<(), WriteError> {
+            (self.function, self.length, self.values).encode(writer)?;
+            writer.write_bytes(self.payload)
+        }
+    }
+
+    #[test]
+    fn composed_message_round_trips() {
+        let header = Header {
+            function: 0x03,
+            length: Be(2),
+            values: [Le(-1), Le(0x1234)],
+            payload: &[0xCA, 0xFE],
+        };
+
+        let mut buffer = [0u8; 9];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor.write(&header).unwrap();
+        assert_eq!(
+            cursor.written(),
+            &[0x03, 0x00, 0x02, 0xFF, 0xFF, 0x34, 0x12, 0xCA, 0xFE]
+        );
+
+        let mut reader = ReadCursor::new(cursor.written());
+        assert_eq!(reader.read::<Header>().unwrap(), header);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn failed_read_does_not_advance() {
+        let mut cursor = ReadCursor::new(&[0x01, 0x02, 0x03]);
+        assert!(cursor.read::<(u8, Le<u32>)>().is_err());
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.read::<(u8, Be<u16>)>().unwrap(), (0x01, Be(0x0203)));
+    }
+
+    #[test]
+    fn failed_write_does_not_advance() {
+        let mut buffer = [0u8; 3];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        assert!(cursor.write(&(Le(1u16), Be(2u16))).is_err());
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn optional_values_round_trip_in_any_position() {
+        let mut buffer = [0u8; 8];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        let value = (None::<u8>, 5u8, Some(Le(0xCAFEu16)));
+        cursor.write(&value).unwrap();
+        assert_eq!(cursor.written(), &[0x00, 0x05, 0x01, 0xFE, 0xCA]);
+
+        let mut reader = ReadCursor::new(cursor.written());
+        assert_eq!(
+            reader.read::<(Option<u8>, u8, Option<Le<u16>>)>().unwrap(),
+            value
+        );
+        assert!(reader.is_empty());
+
+        let mut reader = ReadCursor::new(&[0x02, 0x05]);
+        assert_eq!(
+            reader.read::<Option<u8>>(),
+            Err(ReadError::UnknownDiscriminant {
+                position: 0,
+                value: 2
+            })
+        );
+    }
+
+    #[test]
+    fn byte_arrays_round_trip() {
+        let mut buffer = [0u8; 4];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor.write(&[0xDEu8, 0xAD, 0xBE, 0xEF]).unwrap();
+
+        let mut reader = ReadCursor::new(cursor.written());
+        assert_eq!(reader.read::<[u8; 4]>().unwrap(), [0xDE, 0xAD, 0xBE, 0xEF]);
+    }
+}
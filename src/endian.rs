@@ -0,0 +1,85 @@
+/// Byte order used to encode and decode multi-byte primitives
+///
+/// Implemented by the [`LittleEndian`] and [`BigEndian`] marker types so that
+/// protocol code can be written once and parameterized over byte order
+pub trait ByteOrder {
+    /// Decode a primitive from its byte representation
+    fn decode<T: Primitive>(bytes: T::Bytes) -> T;
+
+    /// Encode a primitive into its byte representation
+    fn encode<T: Primitive>(value: T) -> T::Bytes;
+}
+
+/// Marker type for little-endian byte order
+#[derive(Copy, Clone, Debug)]
+pub enum LittleEndian {}
+
+/// Marker type for big-endian byte order
+#[derive(Copy, Clone, Debug)]
+pub enum BigEndian {}
+
+/// Fixed-size primitive that can be read or written in either byte order
+pub trait Primitive: Copy + Default {
+    /// Byte array representation of the primitive
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    /// Construct the value from its little-endian representation
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+
+    /// Construct the value from its big-endian representation
+    fn from_be_bytes(bytes: Self::Bytes) -> Self;
+
+    /// Return the little-endian representation of the value
+    fn to_le_bytes(self) -> Self::Bytes;
+
+    /// Return the big-endian representation of the value
+    fn to_be_bytes(self) -> Self::Bytes;
+}
+
+impl ByteOrder for LittleEndian {
+    fn decode<T: Primitive>(bytes: T::Bytes) -> T {
+        T::from_le_bytes(bytes)
+    }
+
+    fn encode<T: Primitive>(value: T) -> T::Bytes {
+        value.to_le_bytes()
+    }
+}
+
+impl ByteOrder for BigEndian {
+    fn decode<T: Primitive>(bytes: T::Bytes) -> T {
+        T::from_be_bytes(bytes)
+    }
+
+    fn encode<T: Primitive>(value: T) -> T::Bytes {
+        value.to_be_bytes()
+    }
+}
+
+macro_rules! impl_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl Primitive for $t {
+                type Bytes = [u8; core::mem::size_of::<$t>()];
+
+                fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                    <$t>::from_le_bytes(bytes)
+                }
+
+                fn from_be_bytes(bytes: Self::Bytes) -> Self {
+                    <$t>::from_be_bytes(bytes)
+                }
+
+                fn to_le_bytes(self) -> Self::Bytes {
+                    <$t>::to_le_bytes(self)
+                }
+
+                fn to_be_bytes(self) -> Self::Bytes {
+                    <$t>::to_be_bytes(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
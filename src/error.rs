@@ -0,0 +1,115 @@
+use crate::{ReadError, SavepointError, TrailingBytes, WriteError};
+
+/// Error type that unifies the errors produced by the read and write cursors
+///
+/// Useful for routines that both parse and serialize and want to propagate
+/// either kind of failure using the `?` operator
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CursorError {
+    /// An error occurred while reading
+    Read(ReadError),
+    /// An error occurred while writing
+    Write(WriteError),
+    /// Bytes remained in a cursor that was expected to be empty
+    TrailingBytes(TrailingBytes),
+    /// A savepoint could not be committed or rolled back
+    Savepoint(SavepointError),
+}
+
+impl From<ReadError> for CursorError {
+    fn from(err: ReadError) -> Self {
+        CursorError::Read(err)
+    }
+}
+
+impl From<WriteError> for CursorError {
+    fn from(err: WriteError) -> Self {
+        CursorError::Write(err)
+    }
+}
+
+impl From<TrailingBytes> for CursorError {
+    fn from(err: TrailingBytes) -> Self {
+        CursorError::TrailingBytes(err)
+    }
+}
+
+impl From<SavepointError> for CursorError {
+    fn from(err: SavepointError) -> Self {
+        CursorError::Savepoint(err)
+    }
+}
+
+impl core::fmt::Display for CursorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CursorError::Read(err) => write!(f, "read error: {err}"),
+            CursorError::Write(err) => write!(f, "write error: {err}"),
+            CursorError::TrailingBytes(err) => write!(f, "{err}"),
+            CursorError::Savepoint(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// The message of the wrapped error is included in the `Display` output, so it is not
+/// also reported as the source
+impl core::error::Error for CursorError {}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{ReadCursor, WriteCursor};
+    use std::string::ToString;
+
+    fn copy_u16(input: &[u8], output: &mut [u8]) -> Result<(), CursorError> {
+        let mut reader = ReadCursor::new(input);
+        let value = reader.read_u16_le()?;
+        reader.expect_empty()?;
+        WriteCursor::new(output).write_u16_be(value)?;
+        Ok(())
+    }
+
+    #[test]
+    fn question_mark_converts_each_error_type() {
+        let mut output = [0u8; 1];
+        assert!(matches!(
+            copy_u16(&[0x01], &mut output),
+            Err(CursorError::Read(_))
+        ));
+        assert!(matches!(
+            copy_u16(&[0x01, 0x02, 0x03], &mut output),
+            Err(CursorError::TrailingBytes(_))
+        ));
+        assert!(matches!(
+            copy_u16(&[0x01, 0x02], &mut output),
+            Err(CursorError::Write(_))
+        ));
+    }
+
+    #[test]
+    fn errors_have_human_readable_messages() {
+        let err = ReadCursor::new(&[0x01]).read_u32_le().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "attempted to read 4 bytes at position 0 with only 1 bytes remaining"
+        );
+        assert_eq!(
+            CursorError::from(WriteError::BadSeek { length: 3, pos: 5 }).to_string(),
+            "write error: attempted to seek to position 5 in a buffer of length 3"
+        );
+    }
+
+    #[test]
+    fn errors_can_be_boxed() {
+        let err: std::boxed::Box<dyn core::error::Error> =
+            std::boxed::Box::new(CursorError::from(WriteError::NumericOverflow));
+        assert_eq!(
+            err.to_string(),
+            "write error: numeric overflow in write or seek"
+        );
+        assert!(err.source().is_none());
+    }
+}
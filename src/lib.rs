@@ -5,10 +5,53 @@
 //! * no_std
 //! * panic-free API
 //! * support for transactions
+//!
+//! The optional `alloc` feature provides `VecWriteCursor`, a growable write cursor
+//! with a configurable maximum length.
+//!
+//! The optional `derive` feature provides `#[derive(Decode, Encode)]` macros for the
+//! [`Decode`] and [`Encode`] traits.
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod bits;
+mod block;
+mod chain;
+mod checksum;
+mod codec;
+mod endian;
+mod error;
 mod read;
+mod ring;
+mod savepoint;
+mod scan;
+mod size;
+mod string;
+mod stuffing;
+#[cfg(feature = "alloc")]
+mod vec;
 mod write;
+mod writer;
 
+pub use bits::*;
+pub use block::*;
+pub use chain::*;
+pub use checksum::*;
+pub use codec::*;
+pub use endian::*;
+pub use error::*;
 pub use read::*;
+pub use ring::*;
+pub use savepoint::*;
+pub use scan::*;
+pub use size::*;
+pub use stuffing::*;
+#[cfg(feature = "alloc")]
+pub use vec::*;
 pub use write::*;
+pub use writer::*;
+
+#[cfg(feature = "derive")]
+pub use scursor_derive::{Decode, Encode};
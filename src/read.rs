@@ -1,13 +1,129 @@
+use crate::{BigEndian, ByteOrder, LittleEndian, Primitive, Savepoint, SavepointError};
+
 /// Secure read-only cursor
 #[derive(Copy, Clone, Debug)]
 pub struct ReadCursor<'a> {
     pos: usize,
+    offset: usize,
     input: &'a [u8],
+    partial: bool,
 }
 
-/// Error returned when insufficient data exists to deserialize requested type
-#[derive(Copy, Clone, Debug)]
-pub struct ReadError;
+/// Error type returned when a read cannot be completed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReadError {
+    /// Insufficient data exists to deserialize the requested type
+    InsufficientBytes {
+        /// position of the cursor when the read was attempted
+        position: usize,
+        /// number of bytes requested to be read
+        requested: usize,
+        /// number of bytes remaining in the cursor
+        remaining: usize,
+    },
+    /// LEB128 value was encoded using more bytes than necessary
+    Leb128Overlong {
+        /// position of the start of the encoded value
+        position: usize,
+    },
+    /// LEB128 value does not fit in the requested type
+    Leb128Overflow {
+        /// position of the start of the encoded value
+        position: usize,
+    },
+    /// Number of bits requested from a bit reader is outside the range 1..=64
+    BadBitCount {
+        /// requested number of bits
+        count: u32,
+    },
+    /// Value read did not match the expected fixed (magic) value
+    BadMagic {
+        /// position of the start of the value
+        position: usize,
+    },
+    /// Discriminant read does not correspond to any known variant
+    UnknownDiscriminant {
+        /// position of the start of the discriminant
+        position: usize,
+        /// value of the discriminant
+        value: u64,
+    },
+    /// Bytes remained in a length-delimited section after decoding its contents
+    UnconsumedBytes {
+        /// position of the first unconsumed byte
+        position: usize,
+        /// number of unconsumed bytes
+        count: usize,
+    },
+    /// Scratch buffer provided by the caller cannot hold the requested bytes
+    ScratchTooSmall {
+        /// number of bytes requested to be read
+        requested: usize,
+        /// length of the scratch buffer
+        capacity: usize,
+    },
+    /// Checksum read from the input does not match the one computed over the data
+    ChecksumMismatch {
+        /// position of the start of the checksum
+        position: usize,
+        /// checksum computed over the data
+        expected: u64,
+        /// checksum read from the input
+        received: u64,
+    },
+    /// Requested range of positions does not lie within the bytes consumed by the cursor
+    BadRange {
+        /// start of the requested range
+        start: usize,
+        /// end of the requested range
+        end: usize,
+    },
+    /// Length prefix cannot be converted to a length, e.g. because it is negative
+    BadLength {
+        /// position of the length prefix
+        position: usize,
+    },
+    /// Bytes read as a string are not valid UTF-8
+    InvalidUtf8 {
+        /// position of the first invalid byte
+        position: usize,
+    },
+    /// No NUL terminator was found within the maximum length of a string
+    UnterminatedString {
+        /// position of the start of the string
+        position: usize,
+        /// maximum length of the string in bytes, excluding the terminator
+        max: usize,
+    },
+    /// Byte-stuffed input contains an illegal escape sequence or code
+    BadStuffing {
+        /// position of the start of the illegal sequence
+        position: usize,
+    },
+    /// Delimiter sequence is empty and so would match without consuming any input
+    EmptyDelimiter {
+        /// position of the cursor when the scan was attempted
+        position: usize,
+    },
+    /// Delimiter sequence is longer than the scanner supports
+    DelimiterTooLong {
+        /// length of the delimiter sequence
+        length: usize,
+        /// maximum supported length
+        max: usize,
+    },
+    /// Input ended before the value could be read by a cursor in incomplete-input mode
+    ///
+    /// Unlike [`ReadError::InsufficientBytes`], this does not indicate malformed input.
+    /// The read may succeed once more bytes have been received.
+    Incomplete {
+        /// position of the cursor when the read was attempted
+        position: usize,
+        /// minimum number of additional bytes required to complete the read
+        needed: usize,
+    },
+}
 
 /// Error when asserting that there are no remaining bytes
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -16,21 +132,163 @@ pub struct TrailingBytes {
     pub count: core::num::NonZeroUsize,
 }
 
+impl core::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReadError::InsufficientBytes {
+                position,
+                requested,
+                remaining,
+            } => write!(
+                f,
+                "attempted to read {requested} bytes at position {position} with only {remaining} bytes remaining"
+            ),
+            ReadError::Leb128Overlong { position } => write!(
+                f,
+                "overlong LEB128 encoding at position {position}"
+            ),
+            ReadError::Leb128Overflow { position } => write!(
+                f,
+                "LEB128 value at position {position} overflows the target type"
+            ),
+            ReadError::BadBitCount { count } => {
+                write!(f, "cannot read {count} bits, must be in the range 1..=64")
+            }
+            ReadError::BadMagic { position } => {
+                write!(f, "unexpected magic value at position {position}")
+            }
+            ReadError::UnknownDiscriminant { position, value } => {
+                write!(f, "unknown discriminant {value} at position {position}")
+            }
+            ReadError::UnconsumedBytes { position, count } => write!(
+                f,
+                "{count} bytes at position {position} were not consumed by the section"
+            ),
+            ReadError::ScratchTooSmall { requested, capacity } => write!(
+                f,
+                "cannot copy {requested} bytes into a scratch buffer of length {capacity}"
+            ),
+            ReadError::ChecksumMismatch {
+                position,
+                expected,
+                received,
+            } => write!(
+                f,
+                "checksum mismatch at position {position}, expected {expected:#X} but received {received:#X}"
+            ),
+            ReadError::BadRange { start, end } => write!(
+                f,
+                "range {start}..{end} does not lie within the consumed bytes"
+            ),
+            ReadError::BadLength { position } => {
+                write!(f, "length prefix at position {position} is not a valid length")
+            }
+            ReadError::InvalidUtf8 { position } => {
+                write!(f, "invalid UTF-8 at position {position}")
+            }
+            ReadError::UnterminatedString { position, max } => write!(
+                f,
+                "string at position {position} is not terminated within {max} bytes"
+            ),
+            ReadError::BadStuffing { position } => {
+                write!(f, "illegal byte-stuffing sequence at position {position}")
+            }
+            ReadError::EmptyDelimiter { position } => {
+                write!(f, "empty delimiter used to scan at position {position}")
+            }
+            ReadError::DelimiterTooLong { length, max } => write!(
+                f,
+                "delimiter of {length} bytes exceeds the maximum length of {max} bytes"
+            ),
+            ReadError::Incomplete { position, needed } => write!(
+                f,
+                "input at position {position} is incomplete, at least {needed} more bytes are needed"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ReadError {}
+
+impl core::fmt::Display for TrailingBytes {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} trailing bytes remain in the cursor", self.count)
+    }
+}
+
+impl core::error::Error for TrailingBytes {}
+
 impl<'a> ReadCursor<'a> {
     /// Construct a cursor from a borrowed slice
     pub fn new(input: &'a [u8]) -> Self {
-        Self { pos: 0, input }
+        Self {
+            pos: 0,
+            offset: 0,
+            input,
+            partial: false,
+        }
+    }
+
+    pub(crate) fn with_offset(input: &'a [u8], offset: usize) -> Self {
+        Self {
+            offset,
+            ..Self::new(input)
+        }
+    }
+
+    /// Construct a cursor over a borrowed slice that may be a prefix of a longer stream
+    ///
+    /// Reads that run past the end of the input fail with [`ReadError::Incomplete`]
+    /// instead of [`ReadError::InsufficientBytes`]. Reads that could never complete
+    /// because they would extend the input beyond `isize::MAX` bytes, such as those
+    /// requested by a corrupt length prefix, still fail with
+    /// [`ReadError::InsufficientBytes`]. Child cursors created with
+    /// [`ReadCursor::sub_cursor`] are bounded to bytes that are already available, so
+    /// they always report [`ReadError::InsufficientBytes`].
+    pub fn new_partial(input: &'a [u8]) -> Self {
+        Self {
+            partial: true,
+            ..Self::new(input)
+        }
+    }
+
+    /// Return true if the cursor is in incomplete-input mode
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    /// Record the current position so that it can later be restored
+    pub fn savepoint(&self) -> Savepoint {
+        Savepoint::new(self.input, self.pos)
+    }
+
+    /// Return the cursor to the position recorded in a savepoint
+    ///
+    /// Fails if the savepoint was created by a cursor over a different buffer or
+    /// lies beyond the current position
+    pub fn rollback(&mut self, savepoint: Savepoint) -> Result<(), SavepointError> {
+        self.pos = savepoint.validate(self.input, self.pos, self.offset)?;
+        Ok(())
+    }
+
+    /// Keep everything read since a savepoint, validating that it belongs to this cursor
+    pub fn commit(&self, savepoint: Savepoint) -> Result<(), SavepointError> {
+        savepoint.validate(self.input, self.pos, self.offset)?;
+        Ok(())
     }
 
     /// Read a single unsigned byte from the cursor
     pub fn read_u8(&mut self) -> Result<u8, ReadError> {
         match self.input.get(self.pos) {
             Some(x) => {
-                let pos = self.pos.checked_add(1).ok_or(ReadError)?;
+                let pos = self
+                    .pos
+                    .checked_add(1)
+                    .ok_or_else(|| self.insufficient(1))?;
                 self.pos = pos;
                 Ok(*x)
             }
-            None => Err(ReadError),
+            None => Err(self.insufficient(1)),
         }
     }
 
@@ -51,9 +309,11 @@ impl<'a> ReadCursor<'a> {
 
     /// Return the position of the cursor within the original input slice
     ///
-    /// This is synonymous with the number of bytes consumed by the cursor
+    /// This is synonymous with the number of bytes consumed by the cursor. For
+    /// cursors created using [`ReadCursor::sub_cursor`] or [`ReadCursor::split_at`],
+    /// the position is relative to the start of the input of the root cursor.
     pub fn position(&self) -> usize {
-        self.pos
+        self.offset.saturating_add(self.pos)
     }
 
     /// Perform a transaction on the buffer, returning it to its initial
@@ -89,67 +349,205 @@ impl<'a> ReadCursor<'a> {
 
     /// Read a count of bytes as a borrowed slice
     pub fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], ReadError> {
-        let end = self.pos.checked_add(count).ok_or(ReadError)?;
-        let ret = self.input.get(self.pos..end).ok_or(ReadError)?;
+        let end = self
+            .pos
+            .checked_add(count)
+            .ok_or_else(|| self.insufficient(count))?;
+        let ret = self
+            .input
+            .get(self.pos..end)
+            .ok_or_else(|| self.insufficient(count))?;
         self.pos = end;
         Ok(ret)
     }
+
+    /// Read a count of bytes as a child cursor bounded to those bytes
+    ///
+    /// The parent cursor is advanced past the bytes. The child cursor remembers
+    /// its offset so that its position and errors refer to the original input.
+    pub fn sub_cursor(&mut self, count: usize) -> Result<ReadCursor<'a>, ReadError> {
+        let offset = self.position();
+        let input = self.read_bytes(count)?;
+        Ok(ReadCursor {
+            pos: 0,
+            offset,
+            input,
+            partial: false,
+        })
+    }
+
+    /// Split the remaining bytes into a cursor over the next `count` bytes and a
+    /// cursor over everything after them
+    ///
+    /// Both cursors remember their offset within the original input.
+    pub fn split_at(mut self, count: usize) -> Result<(ReadCursor<'a>, ReadCursor<'a>), ReadError> {
+        let head = self.sub_cursor(count)?;
+        Ok((head, self))
+    }
+
+    /// Read a length prefix of type `T` in byte order `E` and return a child cursor
+    /// bounded to the section of that length
+    ///
+    /// The cursor is only advanced if both the prefix and the section can be read.
+    pub fn read_length_prefixed<T, E>(&mut self) -> Result<ReadCursor<'a>, ReadError>
+    where
+        T: Primitive + TryInto<usize>,
+        E: ByteOrder,
+    {
+        let mut cursor = *self;
+        let position = cursor.position();
+        let length = cursor
+            .read_primitive::<T, E>()?
+            .try_into()
+            .map_err(|_| ReadError::BadLength { position })?;
+        let section = cursor.sub_cursor(length)?;
+        *self = cursor;
+        Ok(section)
+    }
+
+    /// Read a primitive value using the specified byte order
+    ///
+    /// The cursor is only advanced if the entire value can be read
+    pub fn read_primitive<T, E>(&mut self) -> Result<T, ReadError>
+    where
+        T: Primitive,
+        E: ByteOrder,
+    {
+        let mut bytes = T::Bytes::default();
+        let dest = bytes.as_mut();
+        dest.copy_from_slice(self.read_bytes(dest.len())?);
+        Ok(E::decode(bytes))
+    }
+
+    /// Bytes consumed between the absolute position `since` and the current position
+    pub(crate) fn consumed_since(&self, since: usize) -> Result<&'a [u8], ReadError> {
+        let range = ReadError::BadRange {
+            start: since,
+            end: self.position(),
+        };
+        let start = since.checked_sub(self.offset).ok_or(range)?;
+        self.input.get(start..self.pos).ok_or(range)
+    }
+
+    pub(crate) fn insufficient(&self, requested: usize) -> ReadError {
+        // no slice can be longer than isize::MAX, so more input cannot satisfy the read
+        let addressable = self
+            .position()
+            .checked_add(requested)
+            .is_some_and(|end| end <= isize::MAX as usize);
+        if self.partial && addressable {
+            return ReadError::Incomplete {
+                position: self.position(),
+                needed: requested.saturating_sub(self.remaining()),
+            };
+        }
+        ReadError::InsufficientBytes {
+            position: self.position(),
+            requested,
+            remaining: self.remaining(),
+        }
+    }
+}
+
+/// Result of parsing input that may be a prefix of a longer stream
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Streaming<T> {
+    /// The value was parsed
+    Complete(T),
+    /// The input ended before the value could be parsed
+    Incomplete {
+        /// minimum number of additional bytes to buffer before retrying
+        needed: usize,
+    },
+}
+
+/// streaming routines
+impl<'a> ReadCursor<'a> {
+    /// Run a parser in incomplete-input mode as a transaction
+    ///
+    /// If the parser fails because the input ended, the cursor is left unmodified
+    /// and the number of additional bytes to buffer before retrying is returned.
+    /// Any other error indicates malformed input.
+    ///
+    /// ```
+    /// use scursor::{ReadCursor, Streaming};
+    ///
+    /// // a frame consisting of a length byte followed by that many bytes
+    /// let mut cursor = ReadCursor::new(&[0x03, 0xAA]);
+    /// let result = cursor.read_streaming(|cur| {
+    ///     let length = cur.read_u8()?;
+    ///     cur.read_bytes(length as usize)
+    /// });
+    /// assert_eq!(result, Ok(Streaming::Incomplete { needed: 2 }));
+    /// assert_eq!(cursor.position(), 0);
+    /// ```
+    pub fn read_streaming<T, R>(&mut self, parse: T) -> Result<Streaming<R>, ReadError>
+    where
+        T: FnOnce(&mut ReadCursor<'a>) -> Result<R, ReadError>,
+    {
+        let mut cursor = ReadCursor {
+            partial: true,
+            ..*self
+        };
+        match parse(&mut cursor) {
+            Ok(value) => {
+                self.pos = cursor.pos;
+                Ok(Streaming::Complete(value))
+            }
+            Err(ReadError::Incomplete { needed, .. }) => Ok(Streaming::Incomplete { needed }),
+            Err(err) => Err(err),
+        }
+    }
 }
 
 /// little-endian read routines
 impl<'a> ReadCursor<'a> {
     /// Read a u16 from a little-endian representation
     pub fn read_u16_le(&mut self) -> Result<u16, ReadError> {
-        let low = self.read_u8()? as u16;
-        let high = self.read_u8()? as u16;
-        Ok(high << 8 | low)
+        self.read_primitive::<u16, LittleEndian>()
     }
 
     /// Read a i16 from a little-endian representation
     pub fn read_i16_le(&mut self) -> Result<i16, ReadError> {
-        self.read_u16_le().map(|x| x as i16)
+        self.read_primitive::<i16, LittleEndian>()
     }
 
     /// Read a u32 from a little-endian representation
     pub fn read_u32_le(&mut self) -> Result<u32, ReadError> {
-        let low = self.read_u16_le()? as u32;
-        let high = self.read_u16_le()? as u32;
-        Ok(high << 16 | low)
+        self.read_primitive::<u32, LittleEndian>()
     }
 
     /// Read a i32 from a little-endian representation
     pub fn read_i32_le(&mut self) -> Result<i32, ReadError> {
-        self.read_u32_le().map(|x| x as i32)
+        self.read_primitive::<i32, LittleEndian>()
     }
 
     /// Read a 48-bit unsigned number from a little-endian representation, store it in the first 6 bytes of a u64
     pub fn read_u48_le(&mut self) -> Result<u64, ReadError> {
-        let low = self.read_u32_le()? as u64;
-        let high = self.read_u16_le()? as u64;
-        Ok(high << 32 | low)
+        let bytes = self.read_bytes(6)?;
+        let mut value = [0u8; 8];
+        value[0..6].copy_from_slice(bytes);
+        Ok(u64::from_le_bytes(value))
     }
 
     /// Read a u64 number from a little-endian representation
     pub fn read_u64_le(&mut self) -> Result<u64, ReadError> {
-        let low = self.read_u32_le()? as u64;
-        let high = self.read_u32_le()? as u64;
-
-        Ok(high << 32 | low)
+        self.read_primitive::<u64, LittleEndian>()
     }
 
     /// Read a i64 number from a little-endian representation
     pub fn read_i64_le(&mut self) -> Result<i64, ReadError> {
-        self.read_u64_le().map(|x| x as i64)
+        self.read_primitive::<i64, LittleEndian>()
     }
 
     /// Read an IEEE-754 f32 from a little-endian representation
     pub fn read_f32_le(&mut self) -> Result<f32, ReadError> {
-        Ok(f32::from_bits(self.read_u32_le()?))
+        self.read_primitive::<f32, LittleEndian>()
     }
 
     /// Read an IEEE-754 f64 from a little-endian representation
     pub fn read_f64_le(&mut self) -> Result<f64, ReadError> {
-        Ok(f64::from_bits(self.read_u64_le()?))
+        self.read_primitive::<f64, LittleEndian>()
     }
 }
 
@@ -157,41 +555,450 @@ impl<'a> ReadCursor<'a> {
 impl<'a> ReadCursor<'a> {
     /// Read a u16 from a big-endian representation
     pub fn read_u16_be(&mut self) -> Result<u16, ReadError> {
-        let high = self.read_u8()? as u16;
-        let low = self.read_u8()? as u16;
-        Ok(high << 8 | low)
+        self.read_primitive::<u16, BigEndian>()
     }
 
     /// Read a i16 from a big-endian representation
     pub fn read_i16_be(&mut self) -> Result<i16, ReadError> {
-        self.read_u16_be().map(|x| x as i16)
+        self.read_primitive::<i16, BigEndian>()
     }
 
     /// Read a u32 from a big-endian representation
     pub fn read_u32_be(&mut self) -> Result<u32, ReadError> {
-        let high = self.read_u16_be()? as u32;
-        let low = self.read_u16_be()? as u32;
-        Ok(high << 16 | low)
+        self.read_primitive::<u32, BigEndian>()
     }
 
     /// Read a i32 from a big-endian representation
     pub fn read_i32_be(&mut self) -> Result<i32, ReadError> {
-        self.read_u32_be().map(|x| x as i32)
+        self.read_primitive::<i32, BigEndian>()
+    }
+
+    /// Read a 48-bit unsigned number from a big-endian representation, store it in the first 6 bytes of a u64
+    pub fn read_u48_be(&mut self) -> Result<u64, ReadError> {
+        let bytes = self.read_bytes(6)?;
+        let mut value = [0u8; 8];
+        value[2..8].copy_from_slice(bytes);
+        Ok(u64::from_be_bytes(value))
     }
 
     /// Read a u64 from a big-endian representation
     pub fn read_u64_be(&mut self) -> Result<u64, ReadError> {
-        let high = self.read_u32_be()? as u64;
-        let low = self.read_u32_be()? as u64;
-        Ok(high << 32 | low)
+        self.read_primitive::<u64, BigEndian>()
     }
 
     /// Read a i64 from a big-endian representation
     pub fn read_i64_be(&mut self) -> Result<i64, ReadError> {
-        self.read_u64_be().map(|x| x as i64)
+        self.read_primitive::<i64, BigEndian>()
     }
+
+    /// Read an IEEE-754 f32 from a big-endian representation
+    pub fn read_f32_be(&mut self) -> Result<f32, ReadError> {
+        self.read_primitive::<f32, BigEndian>()
+    }
+
+    /// Read an IEEE-754 f64 from a big-endian representation
+    pub fn read_f64_be(&mut self) -> Result<f64, ReadError> {
+        self.read_primitive::<f64, BigEndian>()
+    }
+}
+
+/// peek routines
+impl<'a> ReadCursor<'a> {
+    /// Run a read operation against a copy of the cursor, returning its result
+    ///
+    /// The position of this cursor is never modified, regardless of whether the
+    /// operation succeeds or fails
+    pub fn peek<T, R, E>(&self, read: T) -> Result<R, E>
+    where
+        T: FnOnce(&mut ReadCursor<'a>) -> Result<R, E>,
+    {
+        let mut copy = *self;
+        read(&mut copy)
+    }
+
+    /// Peek a single unsigned byte without advancing the cursor
+    pub fn peek_u8(&self) -> Result<u8, ReadError> {
+        self.peek(|cur| cur.read_u8())
+    }
+
+    /// Peek a count of bytes as a borrowed slice without advancing the cursor
+    pub fn peek_bytes(&self, count: usize) -> Result<&'a [u8], ReadError> {
+        self.peek(|cur| cur.read_bytes(count))
+    }
+
+    /// Peek a primitive value using the specified byte order without advancing the cursor
+    pub fn peek_primitive<T, E>(&self) -> Result<T, ReadError>
+    where
+        T: Primitive,
+        E: ByteOrder,
+    {
+        self.peek(|cur| cur.read_primitive::<T, E>())
+    }
+}
+
+/// little-endian peek routines
+impl<'a> ReadCursor<'a> {
+    /// Peek a u16 from a little-endian representation without advancing the cursor
+    pub fn peek_u16_le(&self) -> Result<u16, ReadError> {
+        self.peek(|cur| cur.read_u16_le())
+    }
+
+    /// Peek a i16 from a little-endian representation without advancing the cursor
+    pub fn peek_i16_le(&self) -> Result<i16, ReadError> {
+        self.peek(|cur| cur.read_i16_le())
+    }
+
+    /// Peek a u32 from a little-endian representation without advancing the cursor
+    pub fn peek_u32_le(&self) -> Result<u32, ReadError> {
+        self.peek(|cur| cur.read_u32_le())
+    }
+
+    /// Peek a i32 from a little-endian representation without advancing the cursor
+    pub fn peek_i32_le(&self) -> Result<i32, ReadError> {
+        self.peek(|cur| cur.read_i32_le())
+    }
+
+    /// Peek a 48-bit unsigned number from a little-endian representation without advancing the cursor
+    pub fn peek_u48_le(&self) -> Result<u64, ReadError> {
+        self.peek(|cur| cur.read_u48_le())
+    }
+
+    /// Peek a u64 from a little-endian representation without advancing the cursor
+    pub fn peek_u64_le(&self) -> Result<u64, ReadError> {
+        self.peek(|cur| cur.read_u64_le())
+    }
+
+    /// Peek a i64 from a little-endian representation without advancing the cursor
+    pub fn peek_i64_le(&self) -> Result<i64, ReadError> {
+        self.peek(|cur| cur.read_i64_le())
+    }
+
+    /// Peek an IEEE-754 f32 from a little-endian representation without advancing the cursor
+    pub fn peek_f32_le(&self) -> Result<f32, ReadError> {
+        self.peek(|cur| cur.read_f32_le())
+    }
+
+    /// Peek an IEEE-754 f64 from a little-endian representation without advancing the cursor
+    pub fn peek_f64_le(&self) -> Result<f64, ReadError> {
+        self.peek(|cur| cur.read_f64_le())
+    }
+}
+
+/// big-endian peek routines
+impl<'a> ReadCursor<'a> {
+    /// Peek a u16 from a big-endian representation without advancing the cursor
+    pub fn peek_u16_be(&self) -> Result<u16, ReadError> {
+        self.peek(|cur| cur.read_u16_be())
+    }
+
+    /// Peek a i16 from a big-endian representation without advancing the cursor
+    pub fn peek_i16_be(&self) -> Result<i16, ReadError> {
+        self.peek(|cur| cur.read_i16_be())
+    }
+
+    /// Peek a u32 from a big-endian representation without advancing the cursor
+    pub fn peek_u32_be(&self) -> Result<u32, ReadError> {
+        self.peek(|cur| cur.read_u32_be())
+    }
+
+    /// Peek a i32 from a big-endian representation without advancing the cursor
+    pub fn peek_i32_be(&self) -> Result<i32, ReadError> {
+        self.peek(|cur| cur.read_i32_be())
+    }
+
+    /// Peek a 48-bit unsigned number from a big-endian representation without advancing the cursor
+    pub fn peek_u48_be(&self) -> Result<u64, ReadError> {
+        self.peek(|cur| cur.read_u48_be())
+    }
+
+    /// Peek a u64 from a big-endian representation without advancing the cursor
+    pub fn peek_u64_be(&self) -> Result<u64, ReadError> {
+        self.peek(|cur| cur.read_u64_be())
+    }
+
+    /// Peek a i64 from a big-endian representation without advancing the cursor
+    pub fn peek_i64_be(&self) -> Result<i64, ReadError> {
+        self.peek(|cur| cur.read_i64_be())
+    }
+
+    /// Peek an IEEE-754 f32 from a big-endian representation without advancing the cursor
+    pub fn peek_f32_be(&self) -> Result<f32, ReadError> {
+        self.peek(|cur| cur.read_f32_be())
+    }
+
+    /// Peek an IEEE-754 f64 from a big-endian representation without advancing the cursor
+    pub fn peek_f64_be(&self) -> Result<f64, ReadError> {
+        self.peek(|cur| cur.read_f64_be())
+    }
+}
+
+/// LEB128 read routines
+impl<'a> ReadCursor<'a> {
+    /// Read an unsigned LEB128 encoded u64
+    ///
+    /// Encodings that use more bytes than necessary or that exceed 64 bits are
+    /// rejected. The cursor is only advanced if the value is read successfully.
+    pub fn read_uleb128_u64(&mut self) -> Result<u64, ReadError> {
+        let mut cursor = *self;
+        let value = decode_uleb128(self.position(), || cursor.read_u8())?;
+        *self = cursor;
+        Ok(value)
+    }
+
+    /// Read a signed LEB128 encoded i64
+    ///
+    /// Encodings that use more bytes than necessary or that exceed 64 bits are
+    /// rejected. The cursor is only advanced if the value is read successfully.
+    pub fn read_sleb128_i64(&mut self) -> Result<i64, ReadError> {
+        let mut cursor = *self;
+        let value = decode_sleb128(self.position(), || cursor.read_u8())?;
+        *self = cursor;
+        Ok(value)
+    }
+
+    /// Read a zigzag encoded i64 stored as an unsigned LEB128 value (protobuf `sint64`)
+    pub fn read_zigzag_i64(&mut self) -> Result<i64, ReadError> {
+        self.read_uleb128_u64().map(unzigzag)
+    }
+}
+
+/// Decode an unsigned LEB128 u64 from a source of bytes, reporting errors at `position`
+pub(crate) fn decode_uleb128(
+    position: usize,
+    mut next: impl FnMut() -> Result<u8, ReadError>,
+) -> Result<u64, ReadError> {
+    let mut value: u64 = 0;
+    let mut shift: u32 = 0;
+    loop {
+        let byte = next()?;
+        let bits = (byte & 0x7F) as u64;
+        // the 10th byte may only contribute the most significant bit
+        if shift == 63 && byte > 0x01 {
+            return Err(ReadError::Leb128Overflow { position });
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            // a trailing zero byte adds nothing to the value
+            if byte == 0 && shift > 0 {
+                return Err(ReadError::Leb128Overlong { position });
+            }
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+/// Decode a signed LEB128 i64 from a source of bytes, reporting errors at `position`
+pub(crate) fn decode_sleb128(
+    position: usize,
+    mut next: impl FnMut() -> Result<u8, ReadError>,
+) -> Result<i64, ReadError> {
+    let mut value: i64 = 0;
+    let mut shift: u32 = 0;
+    let mut previous: u8 = 0;
+    loop {
+        let byte = next()?;
+        let bits = (byte & 0x7F) as i64;
+        // the 10th byte may only contain the sign bit and its extension
+        if shift == 63 && byte != 0x00 && byte != 0x7F {
+            return Err(ReadError::Leb128Overflow { position });
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            // a trailing byte that only repeats the sign of the previous byte adds nothing
+            let previous_negative = previous & 0x40 != 0;
+            if shift > 0
+                && ((byte == 0x00 && !previous_negative) || (byte == 0x7F && previous_negative))
+            {
+                return Err(ReadError::Leb128Overlong { position });
+            }
+            shift += 7;
+            if shift < 64 && byte & 0x40 != 0 {
+                value |= -1i64 << shift;
+            }
+            return Ok(value);
+        }
+        previous = byte;
+        shift += 7;
+    }
+}
+
+/// Undo zigzag encoding of an i64
+pub(crate) fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
 }
 
+/// Implement the typed, peek and LEB128 routines of [`ReadCursor`] for another cursor
+///
+/// The cursor must be `Copy` and provide `position`, `read_u8`, `read_into` and
+/// `read_primitive`, each of which only advances the cursor on success.
+macro_rules! impl_typed_readers {
+    ($cursor:ty) => {
+        /// little-endian read routines
+        impl $cursor {
+            /// Read a u16 from a little-endian representation
+            pub fn read_u16_le(&mut self) -> Result<u16, ReadError> {
+                self.read_primitive::<u16, $crate::LittleEndian>()
+            }
+
+            /// Read a i16 from a little-endian representation
+            pub fn read_i16_le(&mut self) -> Result<i16, ReadError> {
+                self.read_primitive::<i16, $crate::LittleEndian>()
+            }
+
+            /// Read a u32 from a little-endian representation
+            pub fn read_u32_le(&mut self) -> Result<u32, ReadError> {
+                self.read_primitive::<u32, $crate::LittleEndian>()
+            }
+
+            /// Read a i32 from a little-endian representation
+            pub fn read_i32_le(&mut self) -> Result<i32, ReadError> {
+                self.read_primitive::<i32, $crate::LittleEndian>()
+            }
+
+            /// Read a 48-bit unsigned number from a little-endian representation, store it in the first 6 bytes of a u64
+            pub fn read_u48_le(&mut self) -> Result<u64, ReadError> {
+                let mut value = [0u8; 8];
+                self.read_into(&mut value[0..6])?;
+                Ok(u64::from_le_bytes(value))
+            }
+
+            /// Read a u64 number from a little-endian representation
+            pub fn read_u64_le(&mut self) -> Result<u64, ReadError> {
+                self.read_primitive::<u64, $crate::LittleEndian>()
+            }
+
+            /// Read a i64 number from a little-endian representation
+            pub fn read_i64_le(&mut self) -> Result<i64, ReadError> {
+                self.read_primitive::<i64, $crate::LittleEndian>()
+            }
+
+            /// Read an IEEE-754 f32 from a little-endian representation
+            pub fn read_f32_le(&mut self) -> Result<f32, ReadError> {
+                self.read_primitive::<f32, $crate::LittleEndian>()
+            }
+
+            /// Read an IEEE-754 f64 from a little-endian representation
+            pub fn read_f64_le(&mut self) -> Result<f64, ReadError> {
+                self.read_primitive::<f64, $crate::LittleEndian>()
+            }
+        }
+
+        /// big-endian read routines
+        impl $cursor {
+            /// Read a u16 from a big-endian representation
+            pub fn read_u16_be(&mut self) -> Result<u16, ReadError> {
+                self.read_primitive::<u16, $crate::BigEndian>()
+            }
+
+            /// Read a i16 from a big-endian representation
+            pub fn read_i16_be(&mut self) -> Result<i16, ReadError> {
+                self.read_primitive::<i16, $crate::BigEndian>()
+            }
+
+            /// Read a u32 from a big-endian representation
+            pub fn read_u32_be(&mut self) -> Result<u32, ReadError> {
+                self.read_primitive::<u32, $crate::BigEndian>()
+            }
+
+            /// Read a i32 from a big-endian representation
+            pub fn read_i32_be(&mut self) -> Result<i32, ReadError> {
+                self.read_primitive::<i32, $crate::BigEndian>()
+            }
+
+            /// Read a 48-bit unsigned number from a big-endian representation, store it in the first 6 bytes of a u64
+            pub fn read_u48_be(&mut self) -> Result<u64, ReadError> {
+                let mut value = [0u8; 8];
+                self.read_into(&mut value[2..8])?;
+                Ok(u64::from_be_bytes(value))
+            }
+
+            /// Read a u64 from a big-endian representation
+            pub fn read_u64_be(&mut self) -> Result<u64, ReadError> {
+                self.read_primitive::<u64, $crate::BigEndian>()
+            }
+
+            /// Read a i64 from a big-endian representation
+            pub fn read_i64_be(&mut self) -> Result<i64, ReadError> {
+                self.read_primitive::<i64, $crate::BigEndian>()
+            }
+
+            /// Read an IEEE-754 f32 from a big-endian representation
+            pub fn read_f32_be(&mut self) -> Result<f32, ReadError> {
+                self.read_primitive::<f32, $crate::BigEndian>()
+            }
+
+            /// Read an IEEE-754 f64 from a big-endian representation
+            pub fn read_f64_be(&mut self) -> Result<f64, ReadError> {
+                self.read_primitive::<f64, $crate::BigEndian>()
+            }
+        }
+
+        /// typed peek routines
+        impl $cursor {
+            $crate::read::impl_typed_readers!(@peek
+                peek_u16_le, read_u16_le, u16, "Peek a u16 from a little-endian representation without advancing the cursor";
+                peek_i16_le, read_i16_le, i16, "Peek a i16 from a little-endian representation without advancing the cursor";
+                peek_u32_le, read_u32_le, u32, "Peek a u32 from a little-endian representation without advancing the cursor";
+                peek_i32_le, read_i32_le, i32, "Peek a i32 from a little-endian representation without advancing the cursor";
+                peek_u48_le, read_u48_le, u64, "Peek a 48-bit unsigned number from a little-endian representation without advancing the cursor";
+                peek_u64_le, read_u64_le, u64, "Peek a u64 from a little-endian representation without advancing the cursor";
+                peek_i64_le, read_i64_le, i64, "Peek a i64 from a little-endian representation without advancing the cursor";
+                peek_f32_le, read_f32_le, f32, "Peek an IEEE-754 f32 from a little-endian representation without advancing the cursor";
+                peek_f64_le, read_f64_le, f64, "Peek an IEEE-754 f64 from a little-endian representation without advancing the cursor";
+                peek_u16_be, read_u16_be, u16, "Peek a u16 from a big-endian representation without advancing the cursor";
+                peek_i16_be, read_i16_be, i16, "Peek a i16 from a big-endian representation without advancing the cursor";
+                peek_u32_be, read_u32_be, u32, "Peek a u32 from a big-endian representation without advancing the cursor";
+                peek_i32_be, read_i32_be, i32, "Peek a i32 from a big-endian representation without advancing the cursor";
+                peek_u48_be, read_u48_be, u64, "Peek a 48-bit unsigned number from a big-endian representation without advancing the cursor";
+                peek_u64_be, read_u64_be, u64, "Peek a u64 from a big-endian representation without advancing the cursor";
+                peek_i64_be, read_i64_be, i64, "Peek a i64 from a big-endian representation without advancing the cursor";
+                peek_f32_be, read_f32_be, f32, "Peek an IEEE-754 f32 from a big-endian representation without advancing the cursor";
+                peek_f64_be, read_f64_be, f64, "Peek an IEEE-754 f64 from a big-endian representation without advancing the cursor"
+            );
+        }
+
+        /// LEB128 read routines
+        impl $cursor {
+            /// Read an unsigned LEB128 encoded u64
+            ///
+            /// See [`ReadCursor::read_uleb128_u64`](crate::ReadCursor::read_uleb128_u64)
+            pub fn read_uleb128_u64(&mut self) -> Result<u64, ReadError> {
+                let mut cursor = *self;
+                let value = $crate::read::decode_uleb128(self.position(), || cursor.read_u8())?;
+                *self = cursor;
+                Ok(value)
+            }
+
+            /// Read a signed LEB128 encoded i64
+            ///
+            /// See [`ReadCursor::read_sleb128_i64`](crate::ReadCursor::read_sleb128_i64)
+            pub fn read_sleb128_i64(&mut self) -> Result<i64, ReadError> {
+                let mut cursor = *self;
+                let value = $crate::read::decode_sleb128(self.position(), || cursor.read_u8())?;
+                *self = cursor;
+                Ok(value)
+            }
+
+            /// Read a zigzag encoded i64 stored as an unsigned LEB128 value (protobuf `sint64`)
+            pub fn read_zigzag_i64(&mut self) -> Result<i64, ReadError> {
+                self.read_uleb128_u64().map($crate::read::unzigzag)
+            }
+        }
+    };
+    (@peek $($peek:ident, $read:ident, $t:ty, $doc:literal);*) => {
+        $(
+            #[doc = $doc]
+            pub fn $peek(&self) -> Result<$t, ReadError> {
+                let mut copy = *self;
+                copy.$read()
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_typed_readers;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,7 +1015,14 @@ mod tests {
         assert_eq!(cursor.read_u8().unwrap(), 0xFE);
         assert_eq!(cursor.position(), 2);
         assert_eq!(cursor.remaining(), 0);
-        assert!(cursor.read_u8().is_err());
+        assert_eq!(
+            cursor.read_u8(),
+            Err(ReadError::InsufficientBytes {
+                position: 2,
+                requested: 1,
+                remaining: 0
+            })
+        );
         assert_eq!(cursor.position(), 2);
         assert_eq!(cursor.remaining(), 0);
     }
@@ -285,4 +1099,391 @@ mod tests {
         assert_eq!(cursor.read_u64_be().unwrap(), 0x0100FFEEDDCCBBAA);
         assert_eq!(cursor.remaining(), 0);
     }
+
+    #[test]
+    fn can_read_u48_be() {
+        let mut cursor = ReadCursor::new(&[0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]);
+        assert_eq!(cursor.read_u48_be().unwrap(), 0x0000AABBCCDDEEFF);
+        assert_eq!(cursor.remaining(), 0);
+    }
+
+    #[test]
+    fn can_read_f32_be() {
+        let mut cursor = ReadCursor::new(&[0x3F, 0x80, 0x00, 0x00]);
+        assert_eq!(cursor.read_f32_be().unwrap(), 1.0);
+        assert_eq!(cursor.remaining(), 0);
+    }
+
+    #[test]
+    fn can_read_primitive_in_either_byte_order() {
+        fn read_header<E: ByteOrder>(input: &[u8]) -> (u16, i32) {
+            let mut cursor = ReadCursor::new(input);
+            let x = cursor.read_primitive::<u16, E>().unwrap();
+            let y = cursor.read_primitive::<i32, E>().unwrap();
+            assert!(cursor.is_empty());
+            (x, y)
+        }
+
+        let le = read_header::<LittleEndian>(&[0xFE, 0xCA, 0xFE, 0xFF, 0xFF, 0xFF]);
+        let be = read_header::<BigEndian>(&[0xCA, 0xFE, 0xFF, 0xFF, 0xFF, 0xFE]);
+        assert_eq!(le, (0xCAFE, -2));
+        assert_eq!(be, (0xCAFE, -2));
+    }
+
+    #[test]
+    fn failed_multi_byte_read_does_not_advance() {
+        let mut cursor = ReadCursor::new(&[0x01, 0x02, 0x03]);
+        assert_eq!(
+            cursor.read_u32_be(),
+            Err(ReadError::InsufficientBytes {
+                position: 0,
+                requested: 4,
+                remaining: 3
+            })
+        );
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.read_u16_le().unwrap(), 0x0201);
+    }
+
+    #[test]
+    fn read_bytes_error_reports_position_and_counts() {
+        let mut cursor = ReadCursor::new(&[0x01, 0x02, 0x03, 0x04]);
+        cursor.read_u8().unwrap();
+        assert_eq!(
+            cursor.read_bytes(usize::MAX),
+            Err(ReadError::InsufficientBytes {
+                position: 1,
+                requested: usize::MAX,
+                remaining: 3
+            })
+        );
+        assert_eq!(cursor.position(), 1);
+    }
+
+    #[test]
+    fn sub_cursor_is_bounded_and_reports_absolute_position() {
+        let mut cursor = ReadCursor::new(&[0x01, 0x02, 0x03, 0x04, 0x05]);
+        cursor.read_u8().unwrap();
+
+        let mut child = cursor.sub_cursor(2).unwrap();
+        assert_eq!(cursor.position(), 3);
+        assert_eq!(child.position(), 1);
+        assert_eq!(child.remaining(), 2);
+        assert_eq!(child.read_u8().unwrap(), 0x02);
+        assert_eq!(child.position(), 2);
+        assert_eq!(
+            child.read_u16_le(),
+            Err(ReadError::InsufficientBytes {
+                position: 2,
+                requested: 2,
+                remaining: 1
+            })
+        );
+
+        let mut grandchild = child.sub_cursor(1).unwrap();
+        assert_eq!(grandchild.position(), 2);
+        assert_eq!(grandchild.read_u8().unwrap(), 0x03);
+        assert_eq!(grandchild.position(), 3);
+        assert_eq!(cursor.read_all(), &[0x04, 0x05]);
+    }
+
+    #[test]
+    fn sub_cursor_failure_does_not_advance_parent() {
+        let mut cursor = ReadCursor::new(&[0x01, 0x02]);
+        assert!(cursor.sub_cursor(3).is_err());
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn split_at_returns_head_and_tail() {
+        let mut cursor = ReadCursor::new(&[0x01, 0x02, 0x03, 0x04]);
+        cursor.read_u8().unwrap();
+
+        let (mut head, mut tail) = cursor.split_at(2).unwrap();
+        assert_eq!(head.position(), 1);
+        assert_eq!(tail.position(), 3);
+        assert_eq!(head.read_all(), &[0x02, 0x03]);
+        assert_eq!(tail.read_all(), &[0x04]);
+        assert_eq!(tail.position(), 4);
+    }
+
+    #[test]
+    fn peek_does_not_advance_on_success() {
+        let cursor = ReadCursor::new(&[0xCA, 0xFE, 0xBA, 0xBE]);
+        assert_eq!(cursor.peek_u8().unwrap(), 0xCA);
+        assert_eq!(cursor.peek_u16_le().unwrap(), 0xFECA);
+        assert_eq!(cursor.peek_u32_be().unwrap(), 0xCAFEBABE);
+        assert_eq!(cursor.peek_bytes(3).unwrap(), &[0xCA, 0xFE, 0xBA]);
+        assert_eq!(cursor.peek_primitive::<i16, BigEndian>().unwrap(), -13570);
+        assert_eq!(cursor.position(), 0);
+        assert_eq!(cursor.remaining(), 4);
+    }
+
+    #[test]
+    fn peek_does_not_advance_on_failure() {
+        let mut cursor = ReadCursor::new(&[0x01, 0x02, 0x03]);
+        cursor.read_u8().unwrap();
+        assert_eq!(
+            cursor.peek_u32_le(),
+            Err(ReadError::InsufficientBytes {
+                position: 1,
+                requested: 4,
+                remaining: 2
+            })
+        );
+        assert!(cursor.peek_bytes(3).is_err());
+        assert!(cursor.peek_f64_be().is_err());
+        assert_eq!(cursor.position(), 1);
+        assert_eq!(cursor.remaining(), 2);
+    }
+
+    #[test]
+    fn generic_peek_discards_progress() {
+        let cursor = ReadCursor::new(&[0x01, 0x02, 0x03]);
+        let result = cursor.peek(|cur| {
+            let first = cur.read_u8()?;
+            let second = cur.read_u8()?;
+            Ok::<_, ReadError>((first, second))
+        });
+        assert_eq!(result, Ok((0x01, 0x02)));
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn can_read_uleb128() {
+        let tests: [(&[u8], u64); 5] = [
+            (&[0x00], 0),
+            (&[0x7F], 127),
+            (&[0x80, 0x01], 128),
+            (&[0xE5, 0x8E, 0x26], 624485),
+            (
+                &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+                u64::MAX,
+            ),
+        ];
+
+        for (bytes, value) in tests {
+            let mut cursor = ReadCursor::new(bytes);
+            assert_eq!(cursor.read_uleb128_u64().unwrap(), value);
+            assert!(cursor.is_empty());
+        }
+    }
+
+    #[test]
+    fn can_read_sleb128() {
+        let tests: [(&[u8], i64); 6] = [
+            (&[0x00], 0),
+            (&[0x7F], -1),
+            (&[0x3F], 63),
+            (&[0xC0, 0x00], 64),
+            (&[0xC0, 0xBB, 0x78], -123456),
+            (
+                &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F],
+                i64::MIN,
+            ),
+        ];
+
+        for (bytes, value) in tests {
+            let mut cursor = ReadCursor::new(bytes);
+            assert_eq!(cursor.read_sleb128_i64().unwrap(), value);
+            assert!(cursor.is_empty());
+        }
+    }
+
+    #[test]
+    fn can_read_zigzag() {
+        let mut cursor = ReadCursor::new(&[0x00, 0x01, 0x02, 0x03]);
+        assert_eq!(cursor.read_zigzag_i64().unwrap(), 0);
+        assert_eq!(cursor.read_zigzag_i64().unwrap(), -1);
+        assert_eq!(cursor.read_zigzag_i64().unwrap(), 1);
+        assert_eq!(cursor.read_zigzag_i64().unwrap(), -2);
+    }
+
+    #[test]
+    fn rejects_overlong_leb128() {
+        let mut cursor = ReadCursor::new(&[0xAA, 0x80, 0x00]);
+        cursor.read_u8().unwrap();
+        assert_eq!(
+            cursor.read_uleb128_u64(),
+            Err(ReadError::Leb128Overlong { position: 1 })
+        );
+        assert_eq!(cursor.position(), 1);
+
+        let mut cursor = ReadCursor::new(&[0xFF, 0x7F]);
+        assert_eq!(
+            cursor.read_sleb128_i64(),
+            Err(ReadError::Leb128Overlong { position: 0 })
+        );
+        let mut cursor = ReadCursor::new(&[0x81, 0x00]);
+        assert_eq!(
+            cursor.read_sleb128_i64(),
+            Err(ReadError::Leb128Overlong { position: 0 })
+        );
+    }
+
+    #[test]
+    fn rejects_overflowing_leb128() {
+        let mut cursor =
+            ReadCursor::new(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02]);
+        assert_eq!(
+            cursor.read_uleb128_u64(),
+            Err(ReadError::Leb128Overflow { position: 0 })
+        );
+        assert_eq!(cursor.position(), 0);
+
+        // continuation bit set on the 10th byte
+        let bytes = [0x80; 11];
+        assert_eq!(
+            ReadCursor::new(&bytes).read_uleb128_u64(),
+            Err(ReadError::Leb128Overflow { position: 0 })
+        );
+        assert_eq!(
+            ReadCursor::new(&bytes).read_sleb128_i64(),
+            Err(ReadError::Leb128Overflow { position: 0 })
+        );
+
+        let mut cursor =
+            ReadCursor::new(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01]);
+        assert_eq!(
+            cursor.read_sleb128_i64(),
+            Err(ReadError::Leb128Overflow { position: 0 })
+        );
+    }
+
+    #[test]
+    fn truncated_leb128_does_not_advance() {
+        let mut cursor = ReadCursor::new(&[0x80, 0x80]);
+        assert_eq!(
+            cursor.read_uleb128_u64(),
+            Err(ReadError::InsufficientBytes {
+                position: 2,
+                requested: 1,
+                remaining: 0
+            })
+        );
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn can_read_length_prefixed_section() {
+        let mut cursor = ReadCursor::new(&[0x00, 0x02, 0xCA, 0xFE, 0xFF]);
+        let mut section = cursor.read_length_prefixed::<u16, BigEndian>().unwrap();
+        assert_eq!(section.position(), 2);
+        assert_eq!(section.read_all(), &[0xCA, 0xFE]);
+        assert_eq!(cursor.read_all(), &[0xFF]);
+    }
+
+    #[test]
+    fn truncated_length_prefixed_section_does_not_advance() {
+        let mut cursor = ReadCursor::new(&[0x03, 0xCA, 0xFE]);
+        assert_eq!(
+            cursor
+                .read_length_prefixed::<u8, LittleEndian>()
+                .unwrap_err(),
+            ReadError::InsufficientBytes {
+                position: 1,
+                requested: 3,
+                remaining: 2
+            }
+        );
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn negative_length_prefix_is_rejected() {
+        let mut cursor = ReadCursor::new(&[0xAA, 0xFF, 0xFF, 0xCA]);
+        cursor.read_u8().unwrap();
+        assert_eq!(
+            cursor
+                .read_length_prefixed::<i16, LittleEndian>()
+                .unwrap_err(),
+            ReadError::BadLength { position: 1 }
+        );
+        assert_eq!(cursor.position(), 1);
+    }
+
+    #[test]
+    fn partial_cursor_rejects_unaddressable_lengths() {
+        let input = [0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xAA];
+        let mut cursor = ReadCursor::new_partial(&input);
+        cursor.read_u8().unwrap();
+        assert_eq!(
+            cursor
+                .read_length_prefixed::<u64, LittleEndian>()
+                .unwrap_err(),
+            ReadError::InsufficientBytes {
+                position: 9,
+                requested: usize::MAX,
+                remaining: 1
+            }
+        );
+        assert_eq!(cursor.position(), 1);
+        assert_eq!(
+            cursor.read_bytes(usize::MAX),
+            Err(ReadError::InsufficientBytes {
+                position: 1,
+                requested: usize::MAX,
+                remaining: 9
+            })
+        );
+    }
+
+    #[test]
+    fn partial_cursor_reports_needed_bytes() {
+        let mut cursor = ReadCursor::new_partial(&[0x01, 0x02, 0x03]);
+        cursor.read_u8().unwrap();
+        assert_eq!(
+            cursor.read_u32_le(),
+            Err(ReadError::Incomplete {
+                position: 1,
+                needed: 2
+            })
+        );
+        assert_eq!(cursor.position(), 1);
+    }
+
+    #[test]
+    fn sub_cursor_of_partial_cursor_is_complete() {
+        let mut cursor = ReadCursor::new_partial(&[0x01, 0x02, 0x03]);
+        let mut section = cursor.sub_cursor(2).unwrap();
+        assert!(!section.is_partial());
+        assert_eq!(
+            section.read_u32_le(),
+            Err(ReadError::InsufficientBytes {
+                position: 0,
+                requested: 4,
+                remaining: 2
+            })
+        );
+        assert_eq!(
+            cursor.sub_cursor(3).unwrap_err(),
+            ReadError::Incomplete {
+                position: 2,
+                needed: 2
+            }
+        );
+    }
+
+    #[test]
+    fn read_streaming_distinguishes_incomplete_from_malformed() {
+        let mut cursor = ReadCursor::new(&[0x80]);
+        assert_eq!(
+            cursor.read_streaming(|cur| cur.read_uleb128_u64()),
+            Ok(Streaming::Incomplete { needed: 1 })
+        );
+        assert!(!cursor.is_partial());
+
+        let mut cursor = ReadCursor::new(&[0x80, 0x00]);
+        assert_eq!(
+            cursor.read_streaming(|cur| cur.read_uleb128_u64()),
+            Err(ReadError::Leb128Overlong { position: 0 })
+        );
+
+        let mut cursor = ReadCursor::new(&[0x96, 0x01, 0xFF]);
+        assert_eq!(
+            cursor.read_streaming(|cur| cur.read_uleb128_u64()),
+            Ok(Streaming::Complete(150))
+        );
+        assert_eq!(cursor.position(), 2);
+    }
 }
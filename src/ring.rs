@@ -0,0 +1,476 @@
+use crate::read::impl_typed_readers;
+use crate::{ByteOrder, Primitive, ReadCursor, ReadError, WriteError};
+
+/// Fixed-capacity circular buffer for reassembling frames from a byte stream
+///
+/// Incoming bytes are appended with [`RingBuffer::write_bytes`], or read directly into
+/// [`RingBuffer::vacant_mut`] and committed with [`RingBuffer::advance_write`]. Buffered
+/// bytes are parsed in place, without first copying them into a contiguous slice, using
+/// [`RingBuffer::read`].
+#[derive(Debug)]
+pub struct RingBuffer<'a> {
+    buffer: &'a mut [u8],
+    start: usize,
+    len: usize,
+}
+
+/// Read cursor over the possibly wrapped contents of a [`RingBuffer`]
+///
+/// Reads that run past the end of the buffered bytes fail with [`ReadError::Incomplete`]
+/// since more bytes may still arrive. Positions are relative to the oldest buffered byte.
+#[derive(Copy, Clone, Debug)]
+pub struct RingReader<'a> {
+    head: &'a [u8],
+    tail: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> RingBuffer<'a> {
+    /// Construct an empty ring buffer using a borrowed slice as storage
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Total number of bytes that the buffer can hold
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Number of bytes currently buffered
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if no bytes are buffered
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of bytes that may be written before the buffer is full
+    pub fn remaining(&self) -> usize {
+        self.capacity().saturating_sub(self.len)
+    }
+
+    /// Discard all buffered bytes
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+    }
+
+    /// Append bytes to the buffer
+    ///
+    /// Either all of the bytes are written or none of them are
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), WriteError> {
+        if self.remaining() < bytes.len() {
+            return Err(WriteError::WriteOverflow {
+                remaining: self.remaining(),
+                written: bytes.len(),
+            });
+        }
+        let mut bytes = bytes;
+        // the free space consists of at most two contiguous regions
+        while !bytes.is_empty() {
+            let vacant = self.vacant_mut();
+            let count = vacant.len().min(bytes.len());
+            let (src, rest) = bytes.split_at(count);
+            if let Some(dest) = vacant.get_mut(0..count) {
+                dest.copy_from_slice(src);
+            }
+            self.len += count;
+            bytes = rest;
+        }
+        Ok(())
+    }
+
+    /// Contiguous region of free space following the buffered bytes
+    ///
+    /// Useful for reading directly from a port or socket. The region may be shorter than
+    /// [`RingBuffer::remaining`] if the free space wraps around the end of the storage.
+    /// Call [`RingBuffer::advance_write`] to append the bytes written to this region.
+    pub fn vacant_mut(&mut self) -> &mut [u8] {
+        let end = self.start + self.len;
+        let range = if end < self.buffer.len() {
+            end..self.buffer.len()
+        } else {
+            self.wrap(end)..self.start
+        };
+        self.buffer.get_mut(range).unwrap_or(&mut [])
+    }
+
+    /// Append `count` bytes previously written to [`RingBuffer::vacant_mut`]
+    pub fn advance_write(&mut self, count: usize) -> Result<(), WriteError> {
+        let available = self.vacant_mut().len();
+        if available < count {
+            return Err(WriteError::WriteOverflow {
+                remaining: available,
+                written: count,
+            });
+        }
+        self.len += count;
+        Ok(())
+    }
+
+    /// Create a reader over the buffered bytes without consuming them
+    pub fn reader(&self) -> RingReader<'_> {
+        let head_end = self.buffer.len().min(self.start + self.len);
+        let head = self.buffer.get(self.start..head_end).unwrap_or(&[]);
+        let tail = self.buffer.get(0..self.len - head.len()).unwrap_or(&[]);
+        RingReader { head, tail, pos: 0 }
+    }
+
+    /// Discard a count of the oldest buffered bytes
+    pub fn consume(&mut self, count: usize) -> Result<(), ReadError> {
+        if self.len < count {
+            return Err(ReadError::InsufficientBytes {
+                position: 0,
+                requested: count,
+                remaining: self.len,
+            });
+        }
+        self.start = self.wrap(self.start + count);
+        self.len -= count;
+        // maximize the contiguous space available for parsing and writing
+        if self.len == 0 {
+            self.start = 0;
+        }
+        Ok(())
+    }
+
+    /// Parse the buffered bytes, consuming only what was read if the parser succeeds
+    ///
+    /// If an error occurs, nothing is consumed. A [`ReadError::Incomplete`] error
+    /// indicates that the frame is not yet complete.
+    ///
+    /// ```
+    /// use scursor::{BigEndian, ReadError, RingBuffer};
+    ///
+    /// let mut storage = [0u8; 4];
+    /// let mut ring = RingBuffer::new(&mut storage);
+    /// ring.write_bytes(&[0xCA]).unwrap();
+    /// assert!(matches!(
+    ///     ring.read(|reader| reader.read_primitive::<u16, BigEndian>()),
+    ///     Err(ReadError::Incomplete { needed: 1, .. })
+    /// ));
+    /// ring.write_bytes(&[0xFE]).unwrap();
+    /// assert_eq!(ring.read(|reader| reader.read_primitive::<u16, BigEndian>()), Ok(0xCAFE));
+    /// assert!(ring.is_empty());
+    /// ```
+    pub fn read<T, R, E>(&mut self, read: T) -> Result<R, E>
+    where
+        T: FnOnce(&mut RingReader) -> Result<R, E>,
+    {
+        let mut reader = self.reader();
+        let value = read(&mut reader)?;
+        let count = reader.pos;
+        // the reader can never advance beyond the buffered bytes
+        self.start = self.wrap(self.start + count);
+        self.len = self.len.saturating_sub(count);
+        if self.len == 0 {
+            self.start = 0;
+        }
+        Ok(value)
+    }
+
+    fn wrap(&self, index: usize) -> usize {
+        index.checked_sub(self.buffer.len()).unwrap_or(index)
+    }
+}
+
+impl<'a> RingReader<'a> {
+    /// Number of bytes read since the reader was created
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Return the number of bytes remaining to be read
+    pub fn remaining(&self) -> usize {
+        (self.head.len() + self.tail.len()).saturating_sub(self.pos)
+    }
+
+    /// Return true if there are no more bytes remaining to be read
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Perform a transaction on the reader, returning it to its initial
+    /// state if an error occurs
+    pub fn transaction<T, R, E>(&mut self, read: T) -> Result<R, E>
+    where
+        T: FnOnce(&mut RingReader<'a>) -> Result<R, E>,
+    {
+        let mut copy = *self;
+        let value = read(&mut copy)?;
+        *self = copy;
+        Ok(value)
+    }
+
+    /// Read a single unsigned byte
+    pub fn read_u8(&mut self) -> Result<u8, ReadError> {
+        let mut byte = [0u8];
+        self.read_into(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    /// Run a read operation against a copy of the reader, returning its result
+    ///
+    /// The position of this reader is never modified, regardless of whether the
+    /// operation succeeds or fails
+    pub fn peek<T, R, E>(&self, read: T) -> Result<R, E>
+    where
+        T: FnOnce(&mut RingReader<'a>) -> Result<R, E>,
+    {
+        let mut copy = *self;
+        read(&mut copy)
+    }
+
+    /// Peek a single unsigned byte without advancing the reader
+    pub fn peek_u8(&self) -> Result<u8, ReadError> {
+        let mut copy = *self;
+        copy.read_u8()
+    }
+
+    /// Advance the reader a count of bytes
+    pub fn skip(&mut self, count: usize) -> Result<(), ReadError> {
+        if self.remaining() < count {
+            return Err(self.insufficient(count));
+        }
+        self.pos += count;
+        Ok(())
+    }
+
+    /// Fill a slice with the next bytes, which may be split across the wrap-around point
+    ///
+    /// The reader is only advanced if the entire slice can be filled
+    pub fn read_into(&mut self, dest: &mut [u8]) -> Result<(), ReadError> {
+        if self.remaining() < dest.len() {
+            return Err(self.insufficient(dest.len()));
+        }
+        let head = self.head.get(self.pos..).unwrap_or(&[]);
+        let tail_pos = self.pos.saturating_sub(self.head.len());
+        let tail = self.tail.get(tail_pos..).unwrap_or(&[]);
+        let (first, second) = dest.split_at_mut(head.len().min(dest.len()));
+        if let (Some(src), Some(rest)) = (head.get(0..first.len()), tail.get(0..second.len())) {
+            first.copy_from_slice(src);
+            second.copy_from_slice(rest);
+        }
+        self.pos += dest.len();
+        Ok(())
+    }
+
+    /// Read a primitive value using the specified byte order
+    ///
+    /// The reader is only advanced if the entire value can be read
+    pub fn read_primitive<T, E>(&mut self) -> Result<T, ReadError>
+    where
+        T: Primitive,
+        E: ByteOrder,
+    {
+        let mut bytes = T::Bytes::default();
+        self.read_into(bytes.as_mut())?;
+        Ok(E::decode(bytes))
+    }
+
+    /// Peek a primitive value using the specified byte order without advancing the reader
+    pub fn peek_primitive<T, E>(&self) -> Result<T, ReadError>
+    where
+        T: Primitive,
+        E: ByteOrder,
+    {
+        let mut copy = *self;
+        copy.read_primitive::<T, E>()
+    }
+
+    /// Read a count of bytes as a [`ReadCursor`] bounded to those bytes
+    ///
+    /// If the bytes are contiguous in the ring buffer, the cursor borrows them directly.
+    /// Otherwise they are copied into `scratch`, which fails with
+    /// [`ReadError::ScratchTooSmall`] if it cannot hold them. Positions reported by the
+    /// cursor are relative to the start of the ring reader.
+    pub fn read_cursor<'s>(
+        &mut self,
+        count: usize,
+        scratch: &'s mut [u8],
+    ) -> Result<ReadCursor<'s>, ReadError>
+    where
+        'a: 's,
+    {
+        if self.remaining() < count {
+            return Err(self.insufficient(count));
+        }
+        let start = self.pos;
+        let end = start + count;
+        let tail_start = start.saturating_sub(self.head.len());
+        let contiguous = match self.head.get(start..end) {
+            Some(x) => Some(x),
+            None if start >= self.head.len() => self.tail.get(tail_start..tail_start + count),
+            None => None,
+        };
+        let input: &'s [u8] = match contiguous {
+            Some(x) => x,
+            None => {
+                let capacity = scratch.len();
+                let dest = scratch
+                    .get_mut(0..count)
+                    .ok_or(ReadError::ScratchTooSmall {
+                        requested: count,
+                        capacity,
+                    })?;
+                self.read_into(dest)?;
+                self.pos = start;
+                dest
+            }
+        };
+        self.pos = end;
+        Ok(ReadCursor::with_offset(input, start))
+    }
+
+    fn insufficient(&self, requested: usize) -> ReadError {
+        ReadError::Incomplete {
+            position: self.pos,
+            needed: requested.saturating_sub(self.remaining()),
+        }
+    }
+}
+
+impl_typed_readers!(RingReader<'_>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BigEndian;
+
+    fn wrapped(storage: &mut [u8]) -> RingBuffer<'_> {
+        // leave a single 0xFF byte buffered just before the end of the storage
+        let mut ring = RingBuffer::new(storage);
+        let count = ring.capacity() - 2;
+        for _ in 0..=count {
+            ring.write_bytes(&[0xFF]).unwrap();
+        }
+        ring.consume(count).unwrap();
+        ring
+    }
+
+    #[test]
+    fn writes_are_all_or_nothing() {
+        let mut storage = [0u8; 4];
+        let mut ring = RingBuffer::new(&mut storage);
+        ring.write_bytes(&[0x01, 0x02, 0x03]).unwrap();
+        assert_eq!(
+            ring.write_bytes(&[0x04, 0x05]),
+            Err(WriteError::WriteOverflow {
+                remaining: 1,
+                written: 2
+            })
+        );
+        assert_eq!(ring.len(), 3);
+    }
+
+    #[test]
+    fn reads_values_across_the_wrap_around_point() {
+        let mut storage = [0u8; 6];
+        let mut ring = wrapped(&mut storage);
+        ring.write_bytes(&[0xCA, 0xFE, 0xBE, 0xEF]).unwrap();
+
+        let value = ring
+            .read(|reader| {
+                reader.read_u8()?;
+                reader.read_primitive::<u32, BigEndian>()
+            })
+            .unwrap();
+        assert_eq!(value, 0xCAFEBEEF);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn typed_and_leb128_readers_span_the_wrap_around_point() {
+        let mut storage = [0u8; 8];
+        let mut ring = wrapped(&mut storage);
+        ring.write_bytes(&[0xFE, 0xCA, 0xAC, 0x02, 0x7F]).unwrap();
+
+        let value = ring
+            .read(|reader| {
+                reader.skip(1)?;
+                assert_eq!(reader.peek_u16_be()?, 0xFECA);
+                let value = reader.read_u16_le()?;
+                assert_eq!(reader.read_uleb128_u64()?, 300);
+                assert_eq!(reader.peek(|r| r.read_sleb128_i64())?, -1);
+                reader.read_zigzag_i64()?;
+                Ok::<_, ReadError>(value)
+            })
+            .unwrap();
+        assert_eq!(value, 0xCAFE);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn failed_read_consumes_nothing() {
+        let mut storage = [0u8; 8];
+        let mut ring = RingBuffer::new(&mut storage);
+        ring.write_bytes(&[0x05, 0x64, 0x03]).unwrap();
+
+        let result = ring.read(|reader| {
+            reader.read_primitive::<u16, BigEndian>()?;
+            let length = reader.read_u8()?;
+            reader.skip(length as usize)
+        });
+        assert_eq!(
+            result,
+            Err(ReadError::Incomplete {
+                position: 3,
+                needed: 3
+            })
+        );
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.reader().peek_u8().unwrap(), 0x05);
+    }
+
+    #[test]
+    fn vacant_space_is_split_at_wrap_around_point() {
+        let mut storage = [0u8; 6];
+        let mut ring = wrapped(&mut storage);
+        assert_eq!(ring.vacant_mut(), &[0x00]);
+        ring.vacant_mut()[0] = 0x01;
+        ring.advance_write(1).unwrap();
+        assert_eq!(ring.vacant_mut().len(), 4);
+        assert!(ring.advance_write(5).is_err());
+        ring.write_bytes(&[0x02]).unwrap();
+
+        let mut reader = ring.reader();
+        reader.skip(1).unwrap();
+        assert_eq!(reader.read_primitive::<u16, BigEndian>().unwrap(), 0x0102);
+    }
+
+    #[test]
+    fn read_cursor_borrows_or_copies_frames() {
+        let mut storage = [0u8; 6];
+        let mut ring = wrapped(&mut storage);
+        ring.write_bytes(&[0x01, 0x02, 0x03, 0x04]).unwrap();
+
+        let mut reader = ring.reader();
+        reader.skip(1).unwrap();
+        let mut cursor = reader.read_cursor(1, &mut []).unwrap();
+        assert_eq!(cursor.read_u8().unwrap(), 0x01);
+        let mut cursor = reader.read_cursor(2, &mut []).unwrap();
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.read_u16_be().unwrap(), 0x0203);
+
+        let mut reader = ring.reader();
+        reader.skip(1).unwrap();
+        let mut scratch = [0u8; 4];
+        assert_eq!(
+            reader.read_cursor(3, &mut scratch[..2]).unwrap_err(),
+            ReadError::ScratchTooSmall {
+                requested: 3,
+                capacity: 2
+            }
+        );
+        let mut cursor = reader.read_cursor(3, &mut scratch).unwrap();
+        assert_eq!(cursor.position(), 1);
+        assert_eq!(cursor.read_all(), &[0x01, 0x02, 0x03]);
+        assert_eq!(reader.position(), 4);
+    }
+}
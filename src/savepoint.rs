@@ -0,0 +1,191 @@
+/// Position recorded by a cursor that can later be restored
+///
+/// Obtained from [`ReadCursor::savepoint`](crate::ReadCursor::savepoint) or
+/// [`WriteCursor::savepoint`](crate::WriteCursor::savepoint). Savepoints are plain
+/// values, so any number of them may be held at once and rolled back or committed
+/// in any order.
+///
+/// The owning cursor is identified on a best-effort basis by the address and length of
+/// its buffer. Cursors over identical slices, such as two empty buffers, cannot be told
+/// apart and accept each other's savepoints. This is harmless for the cursor since a
+/// savepoint is never restored beyond its current position, so it always remains
+/// within its buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Savepoint {
+    // address and length of the underlying buffer used to identify the cursor
+    origin: usize,
+    length: usize,
+    pos: usize,
+}
+
+/// Error returned when a savepoint cannot be committed or rolled back
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SavepointError {
+    /// Savepoint was created by a cursor over a different buffer
+    WrongCursor,
+    /// Savepoint lies beyond the current position of the cursor
+    BeyondPosition {
+        /// position recorded in the savepoint
+        savepoint: usize,
+        /// current position of the cursor
+        position: usize,
+    },
+}
+
+impl core::fmt::Display for SavepointError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SavepointError::WrongCursor => {
+                f.write_str("savepoint was created by a different cursor")
+            }
+            SavepointError::BeyondPosition {
+                savepoint,
+                position,
+            } => write!(
+                f,
+                "savepoint at position {savepoint} is beyond the cursor position {position}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for SavepointError {}
+
+impl Savepoint {
+    pub(crate) fn new(buffer: &[u8], pos: usize) -> Self {
+        Self {
+            origin: buffer.as_ptr() as usize,
+            length: buffer.len(),
+            pos,
+        }
+    }
+
+    /// Check that the savepoint belongs to a cursor over `buffer` at position `pos`
+    /// and return the recorded position. `offset` is only used to report positions.
+    ///
+    /// The buffer check is best-effort, but the returned position never exceeds `pos`.
+    pub(crate) fn validate(
+        &self,
+        buffer: &[u8],
+        pos: usize,
+        offset: usize,
+    ) -> Result<usize, SavepointError> {
+        if self.origin != buffer.as_ptr() as usize || self.length != buffer.len() {
+            return Err(SavepointError::WrongCursor);
+        }
+        if self.pos > pos {
+            return Err(SavepointError::BeyondPosition {
+                savepoint: offset.saturating_add(self.pos),
+                position: offset.saturating_add(pos),
+            });
+        }
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReadCursor, WriteCursor};
+
+    #[test]
+    fn read_savepoints_support_fallback_branches() {
+        let mut cursor = ReadCursor::new(&[0x01, 0x02, 0x03, 0x04]);
+        let outer = cursor.savepoint();
+        cursor.read_u8().unwrap();
+        let inner = cursor.savepoint();
+        cursor.read_u16_be().unwrap();
+
+        cursor.rollback(inner).unwrap();
+        assert_eq!(cursor.read_u8().unwrap(), 0x02);
+        cursor.rollback(outer).unwrap();
+        assert_eq!(cursor.read_u32_be().unwrap(), 0x01020304);
+
+        assert_eq!(
+            cursor.rollback(inner),
+            Ok(()),
+            "earlier savepoints remain valid after rolling back further"
+        );
+        assert_eq!(cursor.position(), 1);
+    }
+
+    #[test]
+    fn savepoints_beyond_position_are_rejected() {
+        let mut cursor = ReadCursor::new(&[0x01, 0x02]);
+        let start = cursor.savepoint();
+        cursor.read_u8().unwrap();
+        let later = cursor.savepoint();
+        cursor.rollback(start).unwrap();
+        assert_eq!(
+            cursor.commit(later),
+            Err(SavepointError::BeyondPosition {
+                savepoint: 1,
+                position: 0
+            })
+        );
+        assert_eq!(
+            cursor.rollback(later),
+            Err(SavepointError::BeyondPosition {
+                savepoint: 1,
+                position: 0
+            })
+        );
+    }
+
+    #[test]
+    fn savepoints_from_other_cursors_are_rejected() {
+        let input = [0x01, 0x02, 0x03];
+        let mut cursor = ReadCursor::new(&input);
+        let mut other = ReadCursor::new(&input[1..]);
+        let mut sub = cursor.sub_cursor(2).unwrap();
+        assert_eq!(
+            other.rollback(cursor.savepoint()),
+            Err(SavepointError::WrongCursor)
+        );
+        assert_eq!(
+            sub.rollback(cursor.savepoint()),
+            Err(SavepointError::WrongCursor)
+        );
+
+        let mut buffer = [0u8; 3];
+        let mut writer = WriteCursor::new(&mut buffer);
+        let mut buffer = [0u8; 3];
+        let other = WriteCursor::new(&mut buffer);
+        assert_eq!(
+            writer.rollback(other.savepoint()),
+            Err(SavepointError::WrongCursor)
+        );
+    }
+
+    #[test]
+    fn savepoints_over_empty_buffers_are_interchangeable_but_harmless() {
+        let mut cursor = ReadCursor::new(&[]);
+        let other = ReadCursor::new(&[]);
+        // empty slices cannot be distinguished, but position 0 is the only one possible
+        assert_eq!(cursor.rollback(other.savepoint()), Ok(()));
+        assert_eq!(cursor.position(), 0);
+
+        let input = [0x01];
+        let mut non_empty = ReadCursor::new(&input);
+        let sp = non_empty.savepoint();
+        non_empty.read_u8().unwrap();
+        assert_eq!(cursor.rollback(sp), Err(SavepointError::WrongCursor));
+        assert_eq!(
+            non_empty.rollback(cursor.savepoint()),
+            Err(SavepointError::WrongCursor)
+        );
+    }
+
+    #[test]
+    fn write_savepoints_restore_position() {
+        let mut buffer = [0u8; 4];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor.write_u8(0x01).unwrap();
+        let sp = cursor.savepoint();
+        cursor.write_u16_be(0xCAFE).unwrap();
+        cursor.rollback(sp).unwrap();
+        cursor.write_u8(0x02).unwrap();
+        cursor.commit(sp).unwrap();
+        assert_eq!(cursor.written(), &[0x01, 0x02]);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{ReadCursor, ReadError};
+
+/// Iterator over the remaining bytes of a cursor split by a delimiter
+///
+/// Created by [`ReadCursor::split`]. Behaves like [`slice::split`](core::slice),
+/// so consecutive delimiters yield empty slices and the bytes following the last
+/// delimiter are always yielded.
+#[derive(Copy, Clone, Debug)]
+pub struct Split<'a> {
+    remaining: Option<&'a [u8]>,
+    delimiter: u8,
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let input = self.remaining?;
+        match input.iter().position(|x| *x == self.delimiter) {
+            Some(index) => {
+                self.remaining = input.get(index + 1..);
+                input.get(..index)
+            }
+            None => {
+                self.remaining = None;
+                Some(input)
+            }
+        }
+    }
+}
+
+/// Maximum length of a delimiter sequence accepted by [`ReadCursor::read_until_seq`]
+const MAX_DELIMITER: usize = 256;
+
+/// Knuth-Morris-Pratt matcher for a delimiter sequence
+struct Matcher<'d> {
+    delimiter: &'d [u8],
+    // length of the longest proper prefix of delimiter[..=i] that is also its suffix
+    borders: [u8; MAX_DELIMITER],
+}
+
+impl<'d> Matcher<'d> {
+    fn new(delimiter: &'d [u8], position: usize) -> Result<Self, ReadError> {
+        if delimiter.is_empty() {
+            return Err(ReadError::EmptyDelimiter { position });
+        }
+        if delimiter.len() > MAX_DELIMITER {
+            return Err(ReadError::DelimiterTooLong {
+                length: delimiter.len(),
+                max: MAX_DELIMITER,
+            });
+        }
+        let mut matcher = Self {
+            delimiter,
+            borders: [0; MAX_DELIMITER],
+        };
+        let mut state = 0;
+        for (i, byte) in delimiter.iter().enumerate().skip(1) {
+            state = matcher.step(state, *byte);
+            if let Some(x) = matcher.borders.get_mut(i) {
+                // borders are shorter than the delimiter, so they always fit in a u8
+                *x = state as u8;
+            }
+        }
+        Ok(matcher)
+    }
+
+    /// Advance the number of matched delimiter bytes by one input byte
+    fn step(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if self.delimiter.get(state) == Some(&byte) {
+                return state + 1;
+            }
+            match state.checked_sub(1).and_then(|i| self.borders.get(i)) {
+                Some(border) => state = usize::from(*border),
+                None => return 0,
+            }
+        }
+    }
+
+    /// Return the index of the first occurrence of the delimiter, or otherwise the length
+    /// of the longest prefix of the delimiter that ends the input
+    fn find(&self, input: &[u8]) -> Result<usize, usize> {
+        let mut state = 0;
+        for (i, byte) in input.iter().enumerate() {
+            state = self.step(state, *byte);
+            if state == self.delimiter.len() {
+                return Ok(i + 1 - state);
+            }
+        }
+        Err(state)
+    }
+}
+
+/// delimiter scanning routines
+impl<'a> ReadCursor<'a> {
+    /// Read the bytes preceding a delimiter, consuming but not returning the delimiter
+    ///
+    /// If the delimiter is not found the cursor is left unmodified and the error reports
+    /// that at least one more byte is required.
+    ///
+    /// ```
+    /// use scursor::ReadCursor;
+    ///
+    /// let mut cursor = ReadCursor::new(b"$GPGGA,123519*47\r\n");
+    /// assert_eq!(cursor.read_until(b',').unwrap(), b"$GPGGA");
+    /// assert_eq!(cursor.read_until(b'*').unwrap(), b"123519");
+    /// ```
+    pub fn read_until(&mut self, delimiter: u8) -> Result<&'a [u8], ReadError> {
+        let input = self.peek_bytes(self.remaining())?;
+        match input.iter().position(|x| *x == delimiter) {
+            Some(index) => {
+                let value = self.read_bytes(index)?;
+                self.read_u8()?;
+                Ok(value)
+            }
+            None => Err(self.insufficient(input.len().saturating_add(1))),
+        }
+    }
+
+    /// Advance the cursor past the next occurrence of a delimiter, returning the number
+    /// of bytes skipped before it
+    ///
+    /// If the delimiter is not found the cursor is left unmodified
+    pub fn skip_until(&mut self, delimiter: u8) -> Result<usize, ReadError> {
+        self.read_until(delimiter).map(|x| x.len())
+    }
+
+    /// Read the bytes preceding a delimiter sequence, consuming but not returning the
+    /// sequence
+    ///
+    /// Scanning takes O(n + m) time for n remaining bytes and a sequence of length m,
+    /// which may be 1 to 256 bytes long. If the sequence is not found the cursor is
+    /// left unmodified.
+    pub fn read_until_seq(&mut self, delimiter: &[u8]) -> Result<&'a [u8], ReadError> {
+        let input = self.peek_bytes(self.remaining())?;
+        match Matcher::new(delimiter, self.position())?.find(input) {
+            Ok(index) => {
+                let value = self.read_bytes(index)?;
+                self.read_bytes(delimiter.len())?;
+                Ok(value)
+            }
+            Err(partial) => Err(self.insufficient(
+                input
+                    .len()
+                    .saturating_add(delimiter.len())
+                    .saturating_sub(partial),
+            )),
+        }
+    }
+
+    /// Advance the cursor while the predicate holds, returning the number of bytes skipped
+    pub fn skip_while(&mut self, predicate: impl FnMut(u8) -> bool) -> usize {
+        self.read_while(predicate).len()
+    }
+
+    /// Read bytes while the predicate holds
+    pub fn read_while(&mut self, mut predicate: impl FnMut(u8) -> bool) -> &'a [u8] {
+        let input = self.peek_bytes(self.remaining()).unwrap_or(&[]);
+        let count = input
+            .iter()
+            .position(|x| !predicate(*x))
+            .unwrap_or(input.len());
+        self.read_bytes(count).unwrap_or(&[])
+    }
+
+    /// Split the remaining bytes by a delimiter without advancing the cursor
+    ///
+    /// ```
+    /// use scursor::ReadCursor;
+    ///
+    /// let cursor = ReadCursor::new(b"AT+CSQ\r\nOK");
+    /// let mut lines = cursor.split(b'\n');
+    /// assert_eq!(lines.next(), Some(&b"AT+CSQ\r"[..]));
+    /// assert_eq!(lines.next(), Some(&b"OK"[..]));
+    /// assert_eq!(lines.next(), None);
+    /// ```
+    pub fn split(&self, delimiter: u8) -> Split<'a> {
+        Split {
+            remaining: self.peek_bytes(self.remaining()).ok(),
+            delimiter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_until_leaves_cursor_unmodified_when_not_found() {
+        let mut cursor = ReadCursor::new_partial(b"OK\r");
+        assert_eq!(
+            cursor.read_until(b'\n'),
+            Err(ReadError::Incomplete {
+                position: 0,
+                needed: 1
+            })
+        );
+        assert_eq!(cursor.skip_until(b'K').unwrap(), 1);
+        assert_eq!(cursor.read_all(), b"\r");
+    }
+
+    #[test]
+    fn read_until_seq_finds_overlapping_candidates() {
+        let mut cursor = ReadCursor::new(b"a\r\r\nb\r\n");
+        assert_eq!(cursor.read_until_seq(b"\r\n").unwrap(), b"a\r");
+        assert_eq!(
+            cursor.read_until_seq(b""),
+            Err(ReadError::EmptyDelimiter { position: 4 })
+        );
+        assert_eq!(cursor.read_until_seq(b"\r\n").unwrap(), b"b");
+        assert!(cursor.is_empty());
+
+        let mut cursor = ReadCursor::new_partial(b"OK\r");
+        assert_eq!(
+            cursor.read_until_seq(b"\r\n"),
+            Err(ReadError::Incomplete {
+                position: 0,
+                needed: 1
+            })
+        );
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn read_until_seq_uses_longest_partial_match() {
+        let mut cursor = ReadCursor::new(b"xabaabab--");
+        assert_eq!(cursor.read_until_seq(b"abab").unwrap(), b"xaba");
+
+        let mut cursor = ReadCursor::new_partial(b"xababa");
+        assert_eq!(
+            cursor.read_until_seq(b"ababc"),
+            Err(ReadError::Incomplete {
+                position: 0,
+                needed: 2
+            })
+        );
+
+        let mut cursor = ReadCursor::new(&[0; 4]);
+        assert_eq!(
+            cursor.read_until_seq(&[0; 257]),
+            Err(ReadError::DelimiterTooLong {
+                length: 257,
+                max: 256
+            })
+        );
+    }
+
+    #[test]
+    fn read_and_skip_while_predicate_holds() {
+        let mut cursor = ReadCursor::new(b"  123,x");
+        assert_eq!(cursor.skip_while(|x| x == b' '), 2);
+        assert_eq!(cursor.read_while(|x| x.is_ascii_digit()), b"123");
+        assert_eq!(cursor.read_while(|x| x.is_ascii_digit()), b"");
+        assert_eq!(cursor.read_u8().unwrap(), b',');
+        assert_eq!(cursor.read_while(|_| true), b"x");
+    }
+
+    #[test]
+    fn split_matches_slice_semantics() {
+        let input = b",a,,b,";
+        let cursor = ReadCursor::new(input);
+        assert!(cursor.split(b',').eq(input.split(|x| *x == b',')));
+        assert!(ReadCursor::new(&[]).split(b',').eq([&[][..]]));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{Encode, WriteError, Writer};
+
+/// Cursor that tallies the number of bytes that would be written without storing them
+///
+/// Provides the same positioning API as [`WriteCursor`](crate::WriteCursor) over an
+/// unbounded virtual buffer so that a single encoder can be used to measure and write.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SizeCursor {
+    pos: usize,
+    // furthest position reached, which may be beyond the current position after a seek
+    len: usize,
+}
+
+impl SizeCursor {
+    /// Construct a cursor positioned at zero
+    pub fn new() -> Self {
+        Self { pos: 0, len: 0 }
+    }
+
+    /// Current position of the cursor
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Number of bytes that would have been written, i.e. the furthest position reached
+    ///
+    /// Unlike [`SizeCursor::position`], this is not reduced by seeking backwards to
+    /// back-patch data that was already tallied.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if no bytes would have been written
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of bytes that may still be tallied before numeric overflow
+    pub fn remaining(&self) -> usize {
+        usize::MAX - self.pos
+    }
+
+    /// Advance the cursor a count of bytes
+    pub fn skip(&mut self, count: usize) -> Result<(), WriteError> {
+        let new_pos = self
+            .pos
+            .checked_add(count)
+            .ok_or(WriteError::NumericOverflow)?;
+        self.seek_to(new_pos)
+    }
+
+    /// Seek the cursor to an absolute position
+    pub fn seek_to(&mut self, pos: usize) -> Result<(), WriteError> {
+        self.pos = pos;
+        self.len = self.len.max(pos);
+        Ok(())
+    }
+
+    /// Perform a write transaction which returns the cursor to the original
+    /// position and length if an error occurs
+    pub fn transaction<T, R>(&mut self, write: T) -> Result<R, WriteError>
+    where
+        T: FnOnce(&mut SizeCursor) -> Result<R, WriteError>,
+    {
+        let start = *self;
+        let result = write(self);
+        // if an error occurs, rollback to the starting position
+        if result.is_err() {
+            *self = start;
+        }
+        result
+    }
+
+    /// Perform a write transaction at particular position. The cursor is always
+    /// returned to its original position regardless of the success or failure of
+    /// the operation
+    pub fn at_pos<T, R>(&mut self, pos: usize, write: T) -> Result<R, WriteError>
+    where
+        T: Fn(&mut SizeCursor) -> Result<R, WriteError>,
+    {
+        let start = self.pos;
+        self.seek_to(pos)?;
+        let result = write(self);
+        // no matter what happens, go back to the starting position
+        self.pos = start;
+        result
+    }
+
+    /// Tally a slice of bytes
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), WriteError> {
+        self.skip(bytes.len())
+    }
+
+    /// Tally a single u8
+    pub fn write_u8(&mut self, _value: u8) -> Result<(), WriteError> {
+        self.skip(1)
+    }
+
+    /// Tally the encoding of a value, rolling back the position if an error occurs
+    pub fn write<T: Encode + ?Sized>(&mut self, value: &T) -> Result<(), WriteError> {
+        self.transaction(|cur| value.encode(cur))
+    }
+}
+
+impl Writer for SizeCursor {
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn remaining(&self) -> usize {
+        SizeCursor::remaining(self)
+    }
+
+    fn seek_to(&mut self, pos: usize) -> Result<(), WriteError> {
+        SizeCursor::seek_to(self, pos)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), WriteError> {
+        SizeCursor::write_bytes(self, bytes)
+    }
+
+    fn transaction<T, R>(&mut self, write: T) -> Result<R, WriteError>
+    where
+        T: FnOnce(&mut Self) -> Result<R, WriteError>,
+    {
+        SizeCursor::transaction(self, write)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Be, BigEndian, Le, WriteCursor};
+
+    fn encode_frame<W: Writer>(writer: &mut W) -> Result<(), WriteError> {
+        writer.write_u8(0x05)?;
+        writer.write_primitive::<u16, BigEndian>(0xCAFE)?;
+        writer.write_prefixed::<u8, BigEndian, _>(&(Le(1u32), Be(2u16)))?;
+        writer.write_bytes(&[0x01, 0x02, 0x03])
+    }
+
+    #[test]
+    fn same_encoder_measures_and_writes() {
+        let mut size = SizeCursor::new();
+        encode_frame(&mut size).unwrap();
+        assert_eq!(size.position(), 13);
+
+        let mut buffer = [0u8; 13];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        encode_frame(&mut cursor).unwrap();
+        assert_eq!(cursor.remaining(), 0);
+        assert_eq!(
+            cursor.written(),
+            &[0x05, 0xCA, 0xFE, 0x06, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x01, 0x02, 0x03]
+        );
+    }
+
+    #[test]
+    fn encoded_len_matches_written_length() {
+        let value = (0x01u8, [Le(1u16), Le(2)], Some(Be(3u64)));
+        assert_eq!(value.encoded_len(), Ok(14));
+    }
+
+    #[test]
+    fn handles_skip_seek_and_at_pos() {
+        let mut size = SizeCursor::new();
+        size.skip(2).unwrap();
+        size.write_bytes(&[0; 4]).unwrap();
+        size.at_pos(0, |cur| cur.write_bytes(&[0; 2])).unwrap();
+        assert_eq!(size.position(), 6);
+        size.seek_to(1).unwrap();
+        assert_eq!(size.position(), 1);
+        assert_eq!(size.len(), 6);
+        assert_eq!(size.skip(usize::MAX), Err(WriteError::NumericOverflow));
+        assert_eq!(size.position(), 1);
+    }
+
+    #[test]
+    fn measures_length_prefixed_sections() {
+        let mut size = SizeCursor::new();
+        let length = size
+            .write_length_prefixed::<u16, BigEndian>(|cur| cur.write_bytes(&[0; 3]))
+            .unwrap();
+        assert_eq!(length, 3);
+        assert_eq!(size.position(), 5);
+        assert_eq!(size.len(), 5);
+    }
+
+    #[test]
+    fn transaction_rolls_back_position_on_failure() {
+        let mut size = SizeCursor::new();
+        size.write_u8(0).unwrap();
+        let result = size.transaction(|cur| {
+            cur.write_bytes(&[0; 3])?;
+            cur.skip(usize::MAX)
+        });
+        assert_eq!(result, Err(WriteError::NumericOverflow));
+        assert_eq!(size.position(), 1);
+        assert_eq!(size.len(), 1);
+    }
+}
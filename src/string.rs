@@ -0,0 +1,195 @@
+use crate::{ByteOrder, Primitive, ReadCursor, ReadError, WriteCursor, WriteError, Writer};
+
+/// string read routines
+impl<'a> ReadCursor<'a> {
+    /// Read a count of bytes as a UTF-8 string
+    ///
+    /// The cursor is only advanced if all of the bytes can be read and are valid UTF-8
+    pub fn read_utf8(&mut self, count: usize) -> Result<&'a str, ReadError> {
+        let mut cursor = *self;
+        let value = to_str(cursor.position(), cursor.read_bytes(count)?)?;
+        *self = cursor;
+        Ok(value)
+    }
+
+    /// Read a NUL terminated UTF-8 string of at most `max` bytes, excluding the terminator
+    ///
+    /// The terminator is consumed but not included in the returned string. The cursor
+    /// is only advanced if the entire string can be read.
+    ///
+    /// ```
+    /// use scursor::ReadCursor;
+    ///
+    /// let mut cursor = ReadCursor::new(b"pump\0\x01");
+    /// assert_eq!(cursor.read_cstr(16).unwrap(), "pump");
+    /// assert_eq!(cursor.read_u8().unwrap(), 0x01);
+    /// ```
+    pub fn read_cstr(&mut self, max: usize) -> Result<&'a str, ReadError> {
+        let position = self.position();
+        let window = self.remaining().min(max.saturating_add(1));
+        let length = match self.peek_bytes(window)?.iter().position(|x| *x == 0) {
+            Some(x) => x,
+            None if window <= max => return Err(self.insufficient(window.saturating_add(1))),
+            None => return Err(ReadError::UnterminatedString { position, max }),
+        };
+        let mut cursor = *self;
+        let value = cursor.read_utf8(length)?;
+        cursor.read_u8()?;
+        *self = cursor;
+        Ok(value)
+    }
+
+    /// Read a UTF-8 string preceded by its length as a primitive of type `T` in byte order `E`
+    ///
+    /// The cursor is only advanced if the entire string can be read
+    pub fn read_str_prefixed<T, E>(&mut self) -> Result<&'a str, ReadError>
+    where
+        T: Primitive + TryInto<usize>,
+        E: ByteOrder,
+    {
+        let mut cursor = *self;
+        let mut section = cursor.read_length_prefixed::<T, E>()?;
+        let value = section.read_utf8(section.remaining())?;
+        *self = cursor;
+        Ok(value)
+    }
+
+    /// Read a UTF-8 string from a fixed-width field of `width` bytes, removing any
+    /// trailing `pad` bytes
+    ///
+    /// The cursor is only advanced if the entire field can be read
+    pub fn read_str_padded(&mut self, width: usize, pad: u8) -> Result<&'a str, ReadError> {
+        let mut cursor = *self;
+        let position = cursor.position();
+        let field = cursor.read_bytes(width)?;
+        let length = field.iter().rposition(|x| *x != pad).map_or(0, |x| x + 1);
+        let value = to_str(position, field.get(0..length).unwrap_or(&[]))?;
+        *self = cursor;
+        Ok(value)
+    }
+}
+
+fn to_str(position: usize, bytes: &[u8]) -> Result<&str, ReadError> {
+    core::str::from_utf8(bytes).map_err(|err| ReadError::InvalidUtf8 {
+        position: position.saturating_add(err.valid_up_to()),
+    })
+}
+
+/// string write routines
+impl WriteCursor<'_> {
+    /// Write a string of at most `max` bytes followed by a NUL terminator
+    ///
+    /// Strings containing a NUL byte are rejected. Nothing is written on failure.
+    pub fn write_cstr(&mut self, value: &str, max: usize) -> Result<(), WriteError> {
+        Writer::write_cstr(self, value, max)
+    }
+
+    /// Write a string of at most `max` bytes preceded by its length as a primitive of
+    /// type `T` in byte order `E`
+    ///
+    /// Nothing is written on failure.
+    pub fn write_str_prefixed<T, E>(&mut self, value: &str, max: usize) -> Result<(), WriteError>
+    where
+        T: Primitive + TryFrom<usize>,
+        E: ByteOrder,
+    {
+        Writer::write_str_prefixed::<T, E>(self, value, max)
+    }
+
+    /// Write a string into a fixed-width field of `width` bytes, filling the rest of the
+    /// field with `pad`
+    ///
+    /// Nothing is written on failure.
+    pub fn write_str_padded(
+        &mut self,
+        value: &str,
+        width: usize,
+        pad: u8,
+    ) -> Result<(), WriteError> {
+        Writer::write_str_padded(self, value, width, pad)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BigEndian, LittleEndian};
+
+    #[test]
+    fn cstr_requires_terminator_within_max() {
+        let mut cursor = ReadCursor::new(b"abc\0");
+        assert_eq!(
+            cursor.read_cstr(2),
+            Err(ReadError::UnterminatedString {
+                position: 0,
+                max: 2
+            })
+        );
+        assert_eq!(cursor.read_cstr(3).unwrap(), "abc");
+        assert!(cursor.is_empty());
+
+        let mut cursor = ReadCursor::new_partial(b"abc");
+        assert_eq!(
+            cursor.read_cstr(8),
+            Err(ReadError::Incomplete {
+                position: 0,
+                needed: 1
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_is_reported_at_offending_byte() {
+        let mut cursor = ReadCursor::new(&[0x01, b'o', b'k', 0xFF, 0x00]);
+        cursor.read_u8().unwrap();
+        assert_eq!(
+            cursor.read_cstr(8),
+            Err(ReadError::InvalidUtf8 { position: 3 })
+        );
+        assert_eq!(cursor.position(), 1);
+    }
+
+    #[test]
+    fn prefixed_strings_round_trip() {
+        let mut buffer = [0u8; 16];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor
+            .write_str_prefixed::<u16, BigEndian>("héllo", 8)
+            .unwrap();
+        assert_eq!(
+            cursor.write_str_prefixed::<u8, LittleEndian>("too long", 4),
+            Err(WriteError::StringTooLong { length: 8, max: 4 })
+        );
+        assert_eq!(
+            cursor.write_str_prefixed::<u8, LittleEndian>("0123456789", 16),
+            Err(WriteError::WriteOverflow {
+                remaining: 8,
+                written: 11
+            })
+        );
+        assert_eq!(cursor.position(), 8);
+
+        let mut reader = ReadCursor::new(cursor.written());
+        assert_eq!(
+            reader.read_str_prefixed::<u16, BigEndian>().unwrap(),
+            "héllo"
+        );
+    }
+
+    #[test]
+    fn cstr_and_padded_fields_round_trip() {
+        let mut buffer = [0u8; 12];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        assert_eq!(
+            cursor.write_cstr("a\0b", 8),
+            Err(WriteError::InteriorNul { position: 1 })
+        );
+        cursor.write_cstr("dev", 3).unwrap();
+        cursor.write_str_padded("pump", 8, b' ').unwrap();
+        assert_eq!(cursor.written(), b"dev\0pump    ");
+
+        let mut reader = ReadCursor::new(cursor.written());
+        assert_eq!(reader.read_cstr(3).unwrap(), "dev");
+        assert_eq!(reader.read_str_padded(8, b' ').unwrap(), "pump");
+    }
+}
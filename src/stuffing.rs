@@ -0,0 +1,534 @@
+use crate::{ReadCursor, ReadError, WriteCursor, WriteError};
+
+/// SLIP framing as described in RFC 1055
+///
+/// Frames are written with a leading and trailing `END` (0xC0) byte. `END` and `ESC`
+/// (0xDB) bytes in the data are replaced with `ESC ESC_END` and `ESC ESC_ESC`. Decoders
+/// skip leading `END` bytes, so empty frames are never returned.
+#[derive(Copy, Clone, Debug)]
+pub enum Slip {}
+
+/// Consistent Overhead Byte Stuffing, with each frame terminated by a zero byte
+///
+/// Decoders skip leading zero bytes before a frame.
+#[derive(Copy, Clone, Debug)]
+pub enum Cobs {}
+
+/// HDLC-style escaping used by asynchronous PPP framing (RFC 1662)
+///
+/// Frames are written with a leading and trailing flag (0x7E). Flag and escape (0x7D)
+/// bytes in the data are written as the escape byte followed by the byte XOR 0x20.
+/// Decoders accept any escaped byte other than a flag, which aborts the frame, and
+/// skip leading flags so that adjacent frames may share a flag. Empty frames are never
+/// returned.
+#[derive(Copy, Clone, Debug)]
+pub enum Hdlc {}
+
+/// Result of decoding a frame in place
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Unstuffed {
+    /// length of the decoded frame at the start of the buffer
+    pub length: usize,
+    /// number of encoded bytes consumed, including delimiters
+    pub consumed: usize,
+}
+
+mod slip {
+    pub(super) const END: u8 = 0xC0;
+    pub(super) const ESC: u8 = 0xDB;
+    pub(super) const ESC_END: u8 = 0xDC;
+    pub(super) const ESC_ESC: u8 = 0xDD;
+}
+
+mod hdlc {
+    pub(super) const FLAG: u8 = 0x7E;
+    pub(super) const ESC: u8 = 0x7D;
+    pub(super) const XOR: u8 = 0x20;
+}
+
+/// Source of encoded bytes and sink for decoded bytes
+trait Unstuff {
+    fn position(&self) -> usize;
+    fn peek(&self) -> Result<u8, ReadError>;
+    fn next(&mut self) -> Result<u8, ReadError>;
+    fn push(&mut self, value: u8) -> Result<(), ReadError>;
+}
+
+struct CursorUnstuff<'a, 'b> {
+    cursor: ReadCursor<'a>,
+    dest: &'b mut [u8],
+    length: usize,
+}
+
+impl Unstuff for CursorUnstuff<'_, '_> {
+    fn position(&self) -> usize {
+        self.cursor.position()
+    }
+
+    fn peek(&self) -> Result<u8, ReadError> {
+        self.cursor.peek_u8()
+    }
+
+    fn next(&mut self) -> Result<u8, ReadError> {
+        self.cursor.read_u8()
+    }
+
+    fn push(&mut self, value: u8) -> Result<(), ReadError> {
+        let capacity = self.dest.len();
+        match self.dest.get_mut(self.length) {
+            Some(x) => *x = value,
+            None => {
+                return Err(ReadError::ScratchTooSmall {
+                    requested: self.length + 1,
+                    capacity,
+                })
+            }
+        }
+        self.length += 1;
+        Ok(())
+    }
+}
+
+/// Decoded bytes are written behind the read position, which they can never overtake
+struct InPlaceUnstuff<'b> {
+    buffer: &'b mut [u8],
+    pos: usize,
+    length: usize,
+}
+
+impl Unstuff for InPlaceUnstuff<'_> {
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn peek(&self) -> Result<u8, ReadError> {
+        self.buffer
+            .get(self.pos)
+            .copied()
+            .ok_or(ReadError::InsufficientBytes {
+                position: self.pos,
+                requested: 1,
+                remaining: 0,
+            })
+    }
+
+    fn next(&mut self) -> Result<u8, ReadError> {
+        let value = self.peek()?;
+        self.pos += 1;
+        Ok(value)
+    }
+
+    fn push(&mut self, value: u8) -> Result<(), ReadError> {
+        if let Some(x) = self.buffer.get_mut(self.length) {
+            *x = value;
+        }
+        self.length += 1;
+        Ok(())
+    }
+}
+
+fn skip_delimiters<U: Unstuff>(io: &mut U, delimiter: u8) -> Result<(), ReadError> {
+    while io.peek()? == delimiter {
+        io.next()?;
+    }
+    Ok(())
+}
+
+fn decode_slip<U: Unstuff>(io: &mut U) -> Result<(), ReadError> {
+    skip_delimiters(io, slip::END)?;
+    loop {
+        let position = io.position();
+        match io.next()? {
+            slip::END => return Ok(()),
+            slip::ESC => match io.next()? {
+                slip::ESC_END => io.push(slip::END)?,
+                slip::ESC_ESC => io.push(slip::ESC)?,
+                _ => return Err(ReadError::BadStuffing { position }),
+            },
+            x => io.push(x)?,
+        }
+    }
+}
+
+fn decode_cobs<U: Unstuff>(io: &mut U) -> Result<(), ReadError> {
+    skip_delimiters(io, 0)?;
+    loop {
+        let code = io.next()?;
+        if code == 0 {
+            return Ok(());
+        }
+        for _ in 1..code {
+            let position = io.position();
+            match io.next()? {
+                // the delimiter cannot occur within a block
+                0 => return Err(ReadError::BadStuffing { position }),
+                x => io.push(x)?,
+            }
+        }
+        // every block shorter than the maximum is followed by a zero, except the last
+        if code != 0xFF && io.peek()? != 0 {
+            io.push(0)?;
+        }
+    }
+}
+
+fn decode_hdlc<U: Unstuff>(io: &mut U) -> Result<(), ReadError> {
+    skip_delimiters(io, hdlc::FLAG)?;
+    loop {
+        let position = io.position();
+        match io.next()? {
+            hdlc::FLAG => return Ok(()),
+            hdlc::ESC => match io.next()? {
+                // an escaped flag aborts the frame
+                hdlc::FLAG => return Err(ReadError::BadStuffing { position }),
+                x => io.push(x ^ hdlc::XOR)?,
+            },
+            x => io.push(x)?,
+        }
+    }
+}
+
+fn decode_cursor<'a, 'b, F>(
+    cursor: &mut ReadCursor<'a>,
+    dest: &'b mut [u8],
+    decode: F,
+) -> Result<&'b [u8], ReadError>
+where
+    F: FnOnce(&mut CursorUnstuff<'a, 'b>) -> Result<(), ReadError>,
+{
+    let mut io = CursorUnstuff {
+        cursor: *cursor,
+        dest,
+        length: 0,
+    };
+    decode(&mut io)?;
+    *cursor = io.cursor;
+    Ok(io.dest.get(0..io.length).unwrap_or(&[]))
+}
+
+fn decode_in_place<'b, F>(buffer: &'b mut [u8], decode: F) -> Result<Unstuffed, ReadError>
+where
+    F: FnOnce(&mut InPlaceUnstuff<'b>) -> Result<(), ReadError>,
+{
+    let mut io = InPlaceUnstuff {
+        buffer,
+        pos: 0,
+        length: 0,
+    };
+    decode(&mut io)?;
+    Ok(Unstuffed {
+        length: io.length,
+        consumed: io.pos,
+    })
+}
+
+impl Slip {
+    /// Write `data` to the cursor as a single frame
+    ///
+    /// If the entire frame does not fit, the cursor is not advanced and any partially
+    /// written bytes are zeroed
+    pub fn encode(data: &[u8], cursor: &mut WriteCursor) -> Result<(), WriteError> {
+        cursor.zeroing_transaction(|cur| {
+            cur.write_u8(slip::END)?;
+            for byte in data {
+                match *byte {
+                    slip::END => cur.write_bytes(&[slip::ESC, slip::ESC_END])?,
+                    slip::ESC => cur.write_bytes(&[slip::ESC, slip::ESC_ESC])?,
+                    x => cur.write_u8(x)?,
+                }
+            }
+            cur.write_u8(slip::END)
+        })
+    }
+
+    /// Decode the next frame from the cursor into `dest`
+    ///
+    /// The cursor is only advanced if an entire valid frame is read
+    pub fn decode<'b>(
+        cursor: &mut ReadCursor<'_>,
+        dest: &'b mut [u8],
+    ) -> Result<&'b [u8], ReadError> {
+        decode_cursor(cursor, dest, decode_slip)
+    }
+
+    /// Decode the first frame in the buffer, overwriting the start of the buffer
+    pub fn decode_in_place(buffer: &mut [u8]) -> Result<Unstuffed, ReadError> {
+        decode_in_place(buffer, decode_slip)
+    }
+}
+
+impl Cobs {
+    /// Write `data` to the cursor as a single frame followed by a zero byte
+    ///
+    /// If the entire frame does not fit, the cursor is not advanced and any partially
+    /// written bytes are zeroed
+    pub fn encode(data: &[u8], cursor: &mut WriteCursor) -> Result<(), WriteError> {
+        cursor.zeroing_transaction(|cur| {
+            // the code of each block is back-patched once its length is known
+            let mut code_pos = cur.position();
+            let mut code: u8 = 1;
+            cur.write_u8(0)?;
+            for (index, byte) in data.iter().enumerate() {
+                if *byte != 0 {
+                    cur.write_u8(*byte)?;
+                    code += 1;
+                }
+                // a maximal block that ends the data is not followed by an empty block
+                let more = index + 1 < data.len();
+                if *byte == 0 || (code == 0xFF && more) {
+                    cur.at_pos(code_pos, |cur| cur.write_u8(code))?;
+                    code_pos = cur.position();
+                    code = 1;
+                    cur.write_u8(0)?;
+                }
+            }
+            cur.at_pos(code_pos, |cur| cur.write_u8(code))?;
+            cur.write_u8(0)
+        })
+    }
+
+    /// Decode the next frame from the cursor into `dest`
+    ///
+    /// The cursor is only advanced if an entire valid frame is read
+    pub fn decode<'b>(
+        cursor: &mut ReadCursor<'_>,
+        dest: &'b mut [u8],
+    ) -> Result<&'b [u8], ReadError> {
+        decode_cursor(cursor, dest, decode_cobs)
+    }
+
+    /// Decode the first frame in the buffer, overwriting the start of the buffer
+    pub fn decode_in_place(buffer: &mut [u8]) -> Result<Unstuffed, ReadError> {
+        decode_in_place(buffer, decode_cobs)
+    }
+}
+
+impl Hdlc {
+    /// Write `data` to the cursor as a single frame
+    ///
+    /// If the entire frame does not fit, the cursor is not advanced and any partially
+    /// written bytes are zeroed
+    pub fn encode(data: &[u8], cursor: &mut WriteCursor) -> Result<(), WriteError> {
+        cursor.zeroing_transaction(|cur| {
+            cur.write_u8(hdlc::FLAG)?;
+            for byte in data {
+                match *byte {
+                    x @ (hdlc::FLAG | hdlc::ESC) => cur.write_bytes(&[hdlc::ESC, x ^ hdlc::XOR])?,
+                    x => cur.write_u8(x)?,
+                }
+            }
+            cur.write_u8(hdlc::FLAG)
+        })
+    }
+
+    /// Decode the next frame from the cursor into `dest`
+    ///
+    /// The cursor is only advanced if an entire valid frame is read
+    pub fn decode<'b>(
+        cursor: &mut ReadCursor<'_>,
+        dest: &'b mut [u8],
+    ) -> Result<&'b [u8], ReadError> {
+        decode_cursor(cursor, dest, decode_hdlc)
+    }
+
+    /// Decode the first frame in the buffer, overwriting the start of the buffer
+    pub fn decode_in_place(buffer: &mut [u8]) -> Result<Unstuffed, ReadError> {
+        decode_in_place(buffer, decode_hdlc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Encoder = fn(&[u8], &mut WriteCursor) -> Result<(), WriteError>;
+    type Decoder = for<'b> fn(&mut ReadCursor<'_>, &'b mut [u8]) -> Result<&'b [u8], ReadError>;
+    type InPlace = fn(&mut [u8]) -> Result<Unstuffed, ReadError>;
+
+    const CODECS: [(Encoder, Decoder, InPlace); 3] = [
+        (Slip::encode, Slip::decode, Slip::decode_in_place),
+        (Cobs::encode, Cobs::decode, Cobs::decode_in_place),
+        (Hdlc::encode, Hdlc::decode, Hdlc::decode_in_place),
+    ];
+
+    /// Deterministic xorshift generator biased towards the bytes with special meaning
+    struct Random(u32);
+
+    impl Random {
+        fn next(&mut self) -> u32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            self.0
+        }
+
+        fn byte(&mut self) -> u8 {
+            const SPECIAL: [u8; 6] = [0x00, 0xC0, 0xDB, 0x7E, 0x7D, 0xFF];
+            let value = self.next();
+            match value % 4 {
+                0 => SPECIAL[(value >> 8) as usize % SPECIAL.len()],
+                _ => (value >> 16) as u8,
+            }
+        }
+    }
+
+    #[test]
+    fn known_encodings() {
+        let mut buffer = [0u8; 16];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        Slip::encode(&[0x01, 0xC0, 0xDB], &mut cursor).unwrap();
+        assert_eq!(
+            cursor.written(),
+            &[0xC0, 0x01, 0xDB, 0xDC, 0xDB, 0xDD, 0xC0]
+        );
+
+        let mut cursor = WriteCursor::new(&mut buffer);
+        Cobs::encode(&[0x11, 0x22, 0x00, 0x33], &mut cursor).unwrap();
+        assert_eq!(cursor.written(), &[0x03, 0x11, 0x22, 0x02, 0x33, 0x00]);
+
+        let mut buffer = [0u8; 260];
+        let data: [u8; 255] = core::array::from_fn(|i| i as u8 + 1);
+        let mut cursor = WriteCursor::new(&mut buffer);
+        Cobs::encode(&data[..254], &mut cursor).unwrap();
+        assert_eq!(cursor.written()[0], 0xFF);
+        assert_eq!(&cursor.written()[1..255], &data[..254]);
+        assert_eq!(&cursor.written()[255..], &[0x00]);
+        let mut decoded = [0u8; 254];
+        let mut reader = ReadCursor::new(cursor.written());
+        assert_eq!(
+            Cobs::decode(&mut reader, &mut decoded).unwrap(),
+            &data[..254]
+        );
+
+        let mut cursor = WriteCursor::new(&mut buffer);
+        Cobs::encode(&data, &mut cursor).unwrap();
+        assert_eq!(cursor.written()[0], 0xFF);
+        assert_eq!(&cursor.written()[1..255], &data[..254]);
+        assert_eq!(&cursor.written()[255..], &[0x02, 0xFF, 0x00]);
+
+        let mut cursor = WriteCursor::new(&mut buffer);
+        Hdlc::encode(&[0x7E, 0x01, 0x7D], &mut cursor).unwrap();
+        assert_eq!(
+            cursor.written(),
+            &[0x7E, 0x7D, 0x5E, 0x01, 0x7D, 0x5D, 0x7E]
+        );
+    }
+
+    #[test]
+    fn illegal_sequences_are_rejected() {
+        let mut dest = [0u8; 8];
+        let mut cursor = ReadCursor::new(&[0xC0, 0x01, 0xDB, 0x02, 0xC0]);
+        assert_eq!(
+            Slip::decode(&mut cursor, &mut dest),
+            Err(ReadError::BadStuffing { position: 2 })
+        );
+        assert_eq!(cursor.position(), 0);
+
+        let mut cursor = ReadCursor::new(&[0x03, 0x11, 0x00, 0x00]);
+        assert_eq!(
+            Cobs::decode(&mut cursor, &mut dest),
+            Err(ReadError::BadStuffing { position: 2 })
+        );
+
+        let mut cursor = ReadCursor::new(&[0x7E, 0x01, 0x7D, 0x7E]);
+        assert_eq!(
+            Hdlc::decode(&mut cursor, &mut dest),
+            Err(ReadError::BadStuffing { position: 2 })
+        );
+    }
+
+    #[test]
+    fn unterminated_frames_and_small_buffers_are_rejected() {
+        let mut dest = [0u8; 1];
+        let mut cursor = ReadCursor::new_partial(&[0x7E, 0x01]);
+        assert_eq!(
+            Hdlc::decode(&mut cursor, &mut dest),
+            Err(ReadError::Incomplete {
+                position: 2,
+                needed: 1
+            })
+        );
+        let mut cursor = ReadCursor::new(&[0xC0, 0x01, 0x02, 0xC0]);
+        assert_eq!(
+            Slip::decode(&mut cursor, &mut dest),
+            Err(ReadError::ScratchTooSmall {
+                requested: 2,
+                capacity: 1
+            })
+        );
+        assert!(Cobs::decode_in_place(&mut [0x02, 0x01]).is_err());
+    }
+
+    #[test]
+    fn frames_that_do_not_fit_leave_no_partial_data() {
+        for (encode, _, _) in CODECS {
+            let mut buffer = [0xAAu8; 6];
+            let mut cursor = WriteCursor::new(&mut buffer);
+            cursor.write_u8(0x55).unwrap();
+            assert!(encode(&[0x01, 0x02, 0x03, 0x04, 0x05], &mut cursor).is_err());
+            assert_eq!(cursor.position(), 1);
+            assert_eq!(buffer, [0x55, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        }
+    }
+
+    #[test]
+    fn consecutive_frames_are_decoded_in_order() {
+        let mut buffer = [0u8; 32];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        Hdlc::encode(&[0x01, 0x7E], &mut cursor).unwrap();
+        Hdlc::encode(&[0x02], &mut cursor).unwrap();
+
+        let mut reader = ReadCursor::new(cursor.written());
+        let mut dest = [0u8; 4];
+        assert_eq!(Hdlc::decode(&mut reader, &mut dest).unwrap(), &[0x01, 0x7E]);
+        assert_eq!(Hdlc::decode(&mut reader, &mut dest).unwrap(), &[0x02]);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn random_frames_round_trip() {
+        let mut random = Random(0x1234_5678);
+        let mut data = [0u8; 600];
+        let mut encoded = [0u8; 1300];
+        let mut decoded = [0u8; 600];
+        for _ in 0..300 {
+            let length = 1 + random.next() as usize % data.len();
+            let data = &mut data[..length];
+            data.iter_mut().for_each(|x| *x = random.byte());
+            for (encode, decode, decode_in_place) in CODECS {
+                let mut cursor = WriteCursor::new(&mut encoded);
+                encode(data, &mut cursor).unwrap();
+                let written = cursor.position();
+
+                let mut reader = ReadCursor::new(&encoded[..written]);
+                assert_eq!(decode(&mut reader, &mut decoded).unwrap(), data);
+                assert!(reader.is_empty());
+
+                let result = decode_in_place(&mut encoded[..written]).unwrap();
+                assert_eq!(result.consumed, written);
+                assert_eq!(&encoded[..result.length], data);
+            }
+        }
+    }
+
+    #[test]
+    fn random_input_never_panics() {
+        let mut random = Random(0x8765_4321);
+        let mut input = [0u8; 64];
+        let mut decoded = [0u8; 32];
+        for _ in 0..2000 {
+            let length = random.next() as usize % input.len();
+            let input = &mut input[..length];
+            input.iter_mut().for_each(|x| *x = random.byte());
+            for (_, decode, decode_in_place) in CODECS {
+                let mut reader = ReadCursor::new(input);
+                if decode(&mut reader, &mut decoded).is_err() {
+                    assert_eq!(reader.position(), 0);
+                }
+                let mut copy = [0u8; 64];
+                copy[..length].copy_from_slice(input);
+                let _ = decode_in_place(&mut copy[..length]);
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use alloc::vec::Vec;
+
+use crate::{ByteOrder, Encode, Primitive, WriteError, Writer};
+
+/// Growable write cursor backed by a `Vec<u8>`
+///
+/// Behaves like a [`WriteCursor`](crate::WriteCursor) over a zero-initialized buffer of
+/// `max_len` bytes, but only allocates storage as data is written. The maximum length
+/// prevents unbounded growth when encoding untrusted or malformed data.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VecWriteCursor {
+    dest: Vec<u8>,
+    pos: usize,
+    max_len: usize,
+}
+
+impl VecWriteCursor {
+    /// Construct an empty cursor that may grow up to `max_len` bytes
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            dest: Vec::new(),
+            pos: 0,
+            max_len,
+        }
+    }
+
+    /// Construct an empty cursor with storage pre-allocated for `capacity` bytes
+    /// that may grow up to `max_len` bytes
+    ///
+    /// The pre-allocation is limited to `max_len`. If it cannot be satisfied, the cursor
+    /// starts without pre-allocated storage instead.
+    pub fn with_capacity(capacity: usize, max_len: usize) -> Self {
+        let mut dest = Vec::new();
+        // the pre-allocation is only a hint, so failure is not an error
+        let _ = dest.try_reserve_exact(capacity.min(max_len));
+        Self {
+            dest,
+            pos: 0,
+            max_len,
+        }
+    }
+
+    /// Maximum length to which the underlying vector may grow
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Current position of the cursor within the underlying vector
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Get a range within the underlying vector
+    pub fn get(&self, range: core::ops::Range<usize>) -> Option<&[u8]> {
+        self.dest.get(range)
+    }
+
+    /// Advance the cursor a count of bytes
+    pub fn skip(&mut self, count: usize) -> Result<(), WriteError> {
+        let new_pos = self
+            .pos
+            .checked_add(count)
+            .ok_or(WriteError::NumericOverflow)?;
+        self.seek_to(new_pos)
+    }
+
+    /// Seek the cursor to an absolute position no greater than the maximum length
+    ///
+    /// Seeking beyond the data written so far fills the gap with zeros.
+    pub fn seek_to(&mut self, pos: usize) -> Result<(), WriteError> {
+        if self.max_len < pos {
+            return Err(WriteError::BadSeek {
+                length: self.max_len,
+                pos,
+            });
+        }
+        if self.dest.len() < pos {
+            self.reserve_to(pos)?;
+            self.dest.resize(pos, 0);
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    /// Ensure the underlying vector can hold `length` bytes without panicking on allocation
+    fn reserve_to(&mut self, length: usize) -> Result<(), WriteError> {
+        let additional = length.saturating_sub(self.dest.len());
+        self.dest
+            .try_reserve(additional)
+            .map_err(|_| WriteError::AllocationFailed { length })
+    }
+
+    /// Perform a write transaction which returns the cursor to the original
+    /// position if an error occurs
+    pub fn transaction<T, R>(&mut self, write: T) -> Result<R, WriteError>
+    where
+        T: FnOnce(&mut VecWriteCursor) -> Result<R, WriteError>,
+    {
+        let start = self.pos;
+        let result = write(self);
+        // if an error occurs, rollback to the starting position
+        if result.is_err() {
+            self.pos = start;
+        }
+        result
+    }
+
+    /// Perform a write transaction at particular position. The cursor is always
+    /// returned to its original position regardless of the success or failure of
+    /// the operation
+    pub fn at_pos<T, R>(&mut self, pos: usize, write: T) -> Result<R, WriteError>
+    where
+        T: Fn(&mut VecWriteCursor) -> Result<R, WriteError>,
+    {
+        let start = self.pos;
+        self.seek_to(pos)?;
+        let result = write(self);
+        // no matter what happens, go back to the starting position
+        self.pos = start;
+        result
+    }
+
+    /// Write a section of data preceded by its length
+    ///
+    /// See [`WriteCursor::write_length_prefixed`](crate::WriteCursor::write_length_prefixed)
+    pub fn write_length_prefixed<T, E>(
+        &mut self,
+        write: impl FnOnce(&mut VecWriteCursor) -> Result<(), WriteError>,
+    ) -> Result<T, WriteError>
+    where
+        T: Primitive + TryFrom<usize>,
+        E: ByteOrder,
+    {
+        Writer::write_length_prefixed::<T, E>(self, write)
+    }
+
+    /// Return the data that has been written so far as a borrowed slice
+    pub fn written(&self) -> &[u8] {
+        self.dest.get(0..self.pos).unwrap_or(&[])
+    }
+
+    /// Return the data that has been written since a particular write position
+    pub fn written_since(&self, pos: usize) -> Result<&[u8], WriteError> {
+        self.dest.get(pos..self.pos).ok_or(WriteError::BadRange {
+            start: pos,
+            end: self.pos,
+        })
+    }
+
+    /// Number of bytes remaining to be written before the maximum length is reached
+    pub fn remaining(&self) -> usize {
+        self.max_len.saturating_sub(self.pos)
+    }
+
+    /// Write a slice of bytes to the cursor, growing the vector if required
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), WriteError> {
+        let new_pos = self
+            .pos
+            .checked_add(bytes.len())
+            .ok_or(WriteError::NumericOverflow)?;
+        if self.max_len < new_pos {
+            return Err(WriteError::WriteOverflow {
+                remaining: self.remaining(),
+                written: bytes.len(),
+            });
+        }
+        self.reserve_to(new_pos)?;
+        // overwrite any existing data before appending the rest
+        let overlap = self.dest.len().saturating_sub(self.pos).min(bytes.len());
+        let (head, tail) = bytes.split_at(overlap);
+        if let Some(x) = self.dest.get_mut(self.pos..self.pos + overlap) {
+            x.copy_from_slice(head);
+        }
+        self.dest.extend_from_slice(tail);
+        self.pos = new_pos;
+        Ok(())
+    }
+
+    /// Write a single u8 to the cursor, growing the vector if required
+    pub fn write_u8(&mut self, value: u8) -> Result<(), WriteError> {
+        self.write_bytes(&[value])
+    }
+
+    /// Encode a value into the cursor, rolling back the position if an error occurs
+    pub fn write<T: Encode + ?Sized>(&mut self, value: &T) -> Result<(), WriteError> {
+        self.transaction(|cur| value.encode(cur))
+    }
+
+    /// Consume the cursor and return the data that has been written
+    pub fn into_vec(mut self) -> Vec<u8> {
+        self.dest.truncate(self.pos);
+        self.dest
+    }
+}
+
+impl Writer for VecWriteCursor {
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn remaining(&self) -> usize {
+        VecWriteCursor::remaining(self)
+    }
+
+    fn seek_to(&mut self, pos: usize) -> Result<(), WriteError> {
+        VecWriteCursor::seek_to(self, pos)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), WriteError> {
+        VecWriteCursor::write_bytes(self, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Be, BigEndian, Le, ReadCursor};
+
+    #[test]
+    fn grows_as_data_is_written() {
+        let mut cursor = VecWriteCursor::new(1024);
+        cursor.write_u8(0x01).unwrap();
+        cursor.write_u16_be(0xCAFE).unwrap();
+        cursor.write(&(Le(1u32), Be(2u16))).unwrap();
+        assert_eq!(cursor.remaining(), 1015);
+        assert_eq!(
+            cursor.into_vec(),
+            [0x01, 0xCA, 0xFE, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02]
+        );
+    }
+
+    #[test]
+    fn rejects_writes_beyond_max_len() {
+        let mut cursor = VecWriteCursor::with_capacity(usize::MAX, 3);
+        cursor.write_u16_le(0xCAFE).unwrap();
+        assert_eq!(
+            cursor.write_u16_le(0xBEEF),
+            Err(WriteError::WriteOverflow {
+                remaining: 1,
+                written: 2
+            })
+        );
+        assert_eq!(cursor.written(), &[0xFE, 0xCA]);
+        assert_eq!(
+            cursor.seek_to(4),
+            Err(WriteError::BadSeek { length: 3, pos: 4 })
+        );
+    }
+
+    #[test]
+    fn huge_capacity_does_not_panic() {
+        let mut cursor = VecWriteCursor::with_capacity(usize::MAX, usize::MAX);
+        cursor.write_u8(0x01).unwrap();
+        assert_eq!(cursor.into_vec(), [0x01]);
+    }
+
+    #[test]
+    fn huge_seek_reports_allocation_failure() {
+        let mut cursor = VecWriteCursor::new(usize::MAX);
+        assert_eq!(
+            cursor.seek_to(usize::MAX),
+            Err(WriteError::AllocationFailed { length: usize::MAX })
+        );
+        assert_eq!(cursor.position(), 0);
+        cursor.write_u8(0x01).unwrap();
+        assert_eq!(cursor.into_vec(), [0x01]);
+    }
+
+    #[test]
+    fn supports_seek_and_at_pos() {
+        let mut cursor = VecWriteCursor::new(16);
+        cursor.skip(2).unwrap();
+        cursor.write_u8(0xFF).unwrap();
+        cursor.at_pos(0, |cur| cur.write_u16_le(0xCAFE)).unwrap();
+        assert_eq!(cursor.written(), &[0xFE, 0xCA, 0xFF]);
+        assert_eq!(cursor.written_since(1).unwrap(), &[0xCA, 0xFF]);
+        assert_eq!(
+            cursor.written_since(4),
+            Err(WriteError::BadRange { start: 4, end: 3 })
+        );
+    }
+
+    #[test]
+    fn transaction_rolls_back_position_on_failure() {
+        let mut cursor = VecWriteCursor::new(5);
+        cursor.transaction(|cur| cur.write_u16_le(0xCAFE)).unwrap();
+        let result = cursor.transaction(|cur| {
+            cur.write_u16_le(0xDEAD)?;
+            cur.write_u16_le(0xBEEF)
+        });
+        assert!(result.is_err());
+        assert_eq!(cursor.written(), &[0xFE, 0xCA]);
+        cursor.write_u8(0xAA).unwrap();
+        assert_eq!(cursor.into_vec(), [0xFE, 0xCA, 0xAA]);
+    }
+
+    #[test]
+    fn length_prefixed_sections_are_back_patched() {
+        let mut cursor = VecWriteCursor::new(16);
+        let length = cursor
+            .write_length_prefixed::<u16, BigEndian>(|cur| cur.write_bytes(&[0xAA, 0xBB, 0xCC]))
+            .unwrap();
+        assert_eq!(length, 3);
+
+        let bytes = cursor.into_vec();
+        let mut reader = ReadCursor::new(&bytes);
+        let mut section = reader.read_length_prefixed::<u16, BigEndian>().unwrap();
+        assert_eq!(section.read_all(), &[0xAA, 0xBB, 0xCC]);
+    }
+}
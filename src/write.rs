@@ -1,3 +1,5 @@
+use crate::{BigEndian, ByteOrder, LittleEndian, Primitive, Savepoint, SavepointError, Writer};
+
 /// Secure write cursor
 ///
 /// Provides routines for incrementally writing to a borrowed slice
@@ -9,6 +11,7 @@ pub struct WriteCursor<'a> {
 
 /// Error type returned when a seek is requested beyond the bounds of the buffer or numeric range
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum WriteError {
     /// Numeric overflow occurred in a write or seek
     NumericOverflow,
@@ -26,8 +29,91 @@ pub enum WriteError {
         /// requested seek position
         pos: usize,
     },
+    /// Requested range does not lie within the bytes written so far
+    BadRange {
+        /// start of the requested range
+        start: usize,
+        /// end of the requested range
+        end: usize,
+    },
+    /// Length of a length-prefixed section does not fit in the prefix type
+    LengthOverflow {
+        /// number of bytes written in the section
+        length: usize,
+    },
+    /// Number of bits requested for a bit writer is outside the range 1..=64
+    BadBitCount {
+        /// requested number of bits
+        count: u32,
+    },
+    /// Value does not fit in the requested number of bits
+    BitFieldOverflow {
+        /// value that was to be written
+        value: u64,
+        /// requested number of bits
+        count: u32,
+    },
+    /// Growable cursor failed to allocate storage
+    AllocationFailed {
+        /// total length in bytes that could not be allocated
+        length: usize,
+    },
+    /// String is longer than the maximum allowed length
+    StringTooLong {
+        /// length of the string in bytes
+        length: usize,
+        /// maximum length in bytes
+        max: usize,
+    },
+    /// String to be written with a NUL terminator contains a NUL byte
+    InteriorNul {
+        /// index of the NUL byte within the string
+        position: usize,
+    },
 }
 
+impl core::fmt::Display for WriteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WriteError::NumericOverflow => f.write_str("numeric overflow in write or seek"),
+            WriteError::WriteOverflow { remaining, written } => write!(
+                f,
+                "attempted to write {written} bytes with only {remaining} bytes remaining"
+            ),
+            WriteError::BadSeek { length, pos } => write!(
+                f,
+                "attempted to seek to position {pos} in a buffer of length {length}"
+            ),
+            WriteError::BadRange { start, end } => write!(
+                f,
+                "range {start}..{end} does not lie within the written bytes"
+            ),
+            WriteError::LengthOverflow { length } => write!(
+                f,
+                "length-prefixed section of {length} bytes does not fit in the prefix"
+            ),
+            WriteError::BadBitCount { count } => {
+                write!(f, "cannot write {count} bits, must be in the range 1..=64")
+            }
+            WriteError::BitFieldOverflow { value, count } => {
+                write!(f, "value {value} does not fit in {count} bits")
+            }
+            WriteError::AllocationFailed { length } => {
+                write!(f, "failed to allocate storage for {length} bytes")
+            }
+            WriteError::StringTooLong { length, max } => write!(
+                f,
+                "string of {length} bytes exceeds the maximum length of {max} bytes"
+            ),
+            WriteError::InteriorNul { position } => {
+                write!(f, "string contains a NUL byte at index {position}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for WriteError {}
+
 impl<'a> WriteCursor<'a> {
     /// Construct a cursor from a borrowed mutable slice
     pub fn new(dest: &'a mut [u8]) -> WriteCursor<'a> {
@@ -80,6 +166,98 @@ impl<'a> WriteCursor<'a> {
         result
     }
 
+    /// Perform a write transaction which returns the cursor to the original
+    /// position and zeroes the bytes written by the closure if an error occurs
+    ///
+    /// Unlike [`WriteCursor::transaction`], no partially written data is left in the
+    /// buffer after a failure. Everything from the starting position to the end of the
+    /// buffer is zeroed, while the 8 bytes preceding the starting position, enough to
+    /// hold any length prefix back-patched with [`WriteCursor::at_pos`], are restored to
+    /// their previous values. Use [`WriteCursor::restoring_transaction`] if the closure
+    /// may modify data further back.
+    ///
+    /// ```
+    /// use scursor::WriteCursor;
+    ///
+    /// let mut buffer = [0u8; 3];
+    /// let mut cursor = WriteCursor::new(&mut buffer);
+    /// let result = cursor.zeroing_transaction(|cur| cur.write_bytes(&[0xAA, 0xBB, 0xCC, 0xDD]));
+    /// assert!(result.is_err());
+    /// assert_eq!(cursor.position(), 0);
+    /// assert_eq!(cursor.get(0..3).unwrap(), &[0x00, 0x00, 0x00]);
+    /// ```
+    pub fn zeroing_transaction<T, R>(&mut self, write: T) -> Result<R, WriteError>
+    where
+        T: FnOnce(&mut WriteCursor) -> Result<R, WriteError>,
+    {
+        self.restoring_transaction(&mut [0; 8], write)
+    }
+
+    /// Perform a write transaction which returns the cursor to the original position
+    /// and undoes the bytes written by the closure if an error occurs
+    ///
+    /// Before the closure is invoked, the bytes immediately preceding the starting
+    /// position are copied into `snapshot`, up to its length. On failure, this window
+    /// is restored from the snapshot and everything from the starting position to the
+    /// end of the buffer is zeroed. The snapshot should therefore cover any previously
+    /// written data that the closure may modify, e.g. by back-patching a header with
+    /// [`WriteCursor::at_pos`].
+    pub fn restoring_transaction<T, R>(
+        &mut self,
+        snapshot: &mut [u8],
+        write: T,
+    ) -> Result<R, WriteError>
+    where
+        T: FnOnce(&mut WriteCursor) -> Result<R, WriteError>,
+    {
+        let start = self.pos;
+        let window_start = start.saturating_sub(snapshot.len());
+        let saved = match (
+            self.dest.get(window_start..start),
+            snapshot.get_mut(0..start - window_start),
+        ) {
+            (Some(src), Some(dest)) => {
+                dest.copy_from_slice(src);
+                dest
+            }
+            _ => &mut [],
+        };
+
+        let result = write(self);
+
+        if result.is_err() {
+            self.pos = start;
+            if let Some(window) = self.dest.get_mut(window_start..start) {
+                window.copy_from_slice(saved);
+            }
+            if let Some(rest) = self.dest.get_mut(start..) {
+                rest.fill(0);
+            }
+        }
+        result
+    }
+
+    /// Record the current position so that it can later be restored
+    pub fn savepoint(&self) -> Savepoint {
+        Savepoint::new(self.dest, self.pos)
+    }
+
+    /// Return the cursor to the position recorded in a savepoint
+    ///
+    /// Like [`WriteCursor::transaction`], only the position is restored. Fails if the
+    /// savepoint was created by a cursor over a different buffer or lies beyond the
+    /// current position
+    pub fn rollback(&mut self, savepoint: Savepoint) -> Result<(), SavepointError> {
+        self.pos = savepoint.validate(self.dest, self.pos, 0)?;
+        Ok(())
+    }
+
+    /// Keep everything written since a savepoint, validating that it belongs to this cursor
+    pub fn commit(&self, savepoint: Savepoint) -> Result<(), SavepointError> {
+        savepoint.validate(self.dest, self.pos, 0)?;
+        Ok(())
+    }
+
     /// Perform a write transaction at particular position. The cursor is always
     /// returned to its original position regardless of the success or failure of
     /// the operation
@@ -95,6 +273,36 @@ impl<'a> WriteCursor<'a> {
         result
     }
 
+    /// Write a section of data preceded by its length
+    ///
+    /// Space for a length prefix of type `T` is reserved and the closure is invoked to
+    /// write the body. The number of bytes written by the closure is then written into
+    /// the prefix using byte order `E` and returned. The operation is performed as a
+    /// transaction so the prefix is also rolled back if the body cannot be written or
+    /// if its length does not fit in `T`.
+    ///
+    /// ```
+    /// use scursor::{BigEndian, WriteCursor};
+    ///
+    /// let mut buffer = [0u8; 5];
+    /// let mut cursor = WriteCursor::new(&mut buffer);
+    /// let length = cursor
+    ///     .write_length_prefixed::<u16, BigEndian>(|cur| cur.write_bytes(&[0xAA, 0xBB, 0xCC]))
+    ///     .unwrap();
+    /// assert_eq!(length, 3);
+    /// assert_eq!(cursor.written(), &[0x00, 0x03, 0xAA, 0xBB, 0xCC]);
+    /// ```
+    pub fn write_length_prefixed<T, E>(
+        &mut self,
+        write: impl FnOnce(&mut WriteCursor) -> Result<(), WriteError>,
+    ) -> Result<T, WriteError>
+    where
+        T: Primitive + TryFrom<usize>,
+        E: ByteOrder,
+    {
+        Writer::write_length_prefixed::<T, E>(self, write)
+    }
+
     /// Return the data that has been written so far as a borrowed slice
     pub fn written(&self) -> &[u8] {
         self.dest.get(0..self.pos).unwrap_or(&[])
@@ -102,10 +310,10 @@ impl<'a> WriteCursor<'a> {
 
     /// Return the data that has been written since a particular write position
     pub fn written_since(&'a self, pos: usize) -> Result<&'a [u8], WriteError> {
-        match self.dest.get(pos..self.pos) {
-            Some(x) => Ok(x),
-            None => Err(WriteError::NumericOverflow),
-        }
+        self.dest.get(pos..self.pos).ok_or(WriteError::BadRange {
+            start: pos,
+            end: self.pos,
+        })
     }
 
     /// Number of bytes remaining to be written
@@ -147,28 +355,59 @@ impl<'a> WriteCursor<'a> {
             }),
         }
     }
+
+    /// Write a primitive value using the specified byte order
+    pub fn write_primitive<T, E>(&mut self, value: T) -> Result<(), WriteError>
+    where
+        T: Primitive,
+        E: ByteOrder,
+    {
+        self.write_bytes(E::encode(value).as_ref())
+    }
+}
+
+impl Writer for WriteCursor<'_> {
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn remaining(&self) -> usize {
+        WriteCursor::remaining(self)
+    }
+
+    fn seek_to(&mut self, pos: usize) -> Result<(), WriteError> {
+        WriteCursor::seek_to(self, pos)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), WriteError> {
+        WriteCursor::write_bytes(self, bytes)
+    }
+
+    fn write_u8(&mut self, value: u8) -> Result<(), WriteError> {
+        WriteCursor::write_u8(self, value)
+    }
 }
 
 /// little-endian write routines
 impl<'a> WriteCursor<'a> {
     /// Write a u16 in little-endian format
     pub fn write_u16_le(&mut self, value: u16) -> Result<(), WriteError> {
-        self.write_bytes(&value.to_le_bytes())
+        self.write_primitive::<u16, LittleEndian>(value)
     }
 
     /// Write a i16 in little-endian format
     pub fn write_i16_le(&mut self, value: i16) -> Result<(), WriteError> {
-        self.write_bytes(&value.to_le_bytes())
+        self.write_primitive::<i16, LittleEndian>(value)
     }
 
     /// Write a u32 in little-endian format
     pub fn write_u32_le(&mut self, value: u32) -> Result<(), WriteError> {
-        self.write_bytes(&value.to_le_bytes())
+        self.write_primitive::<u32, LittleEndian>(value)
     }
 
     /// Write a i32 in little-endian format
     pub fn write_i32_le(&mut self, value: i32) -> Result<(), WriteError> {
-        self.write_bytes(&value.to_le_bytes())
+        self.write_primitive::<i32, LittleEndian>(value)
     }
 
     /// Write the lower 6-bytes of a u64 (u48) in little-endian format
@@ -177,14 +416,24 @@ impl<'a> WriteCursor<'a> {
         self.write_bytes(&bytes[0..6])
     }
 
+    /// Write a u64 in little-endian format
+    pub fn write_u64_le(&mut self, value: u64) -> Result<(), WriteError> {
+        self.write_primitive::<u64, LittleEndian>(value)
+    }
+
+    /// Write a i64 in little-endian format
+    pub fn write_i64_le(&mut self, value: i64) -> Result<(), WriteError> {
+        self.write_primitive::<i64, LittleEndian>(value)
+    }
+
     /// Write an IEEE-754 f32 in little endian format
     pub fn write_f32_le(&mut self, value: f32) -> Result<(), WriteError> {
-        self.write_bytes(&value.to_le_bytes())
+        self.write_primitive::<f32, LittleEndian>(value)
     }
 
     /// Write an IEEE-754 f64 in little endian format
     pub fn write_f64_le(&mut self, value: f64) -> Result<(), WriteError> {
-        self.write_bytes(&value.to_le_bytes())
+        self.write_primitive::<f64, LittleEndian>(value)
     }
 }
 
@@ -192,7 +441,66 @@ impl<'a> WriteCursor<'a> {
 impl<'a> WriteCursor<'a> {
     /// Write a u16 in big-endian format
     pub fn write_u16_be(&mut self, value: u16) -> Result<(), WriteError> {
-        self.write_bytes(&value.to_be_bytes())
+        self.write_primitive::<u16, BigEndian>(value)
+    }
+
+    /// Write a i16 in big-endian format
+    pub fn write_i16_be(&mut self, value: i16) -> Result<(), WriteError> {
+        self.write_primitive::<i16, BigEndian>(value)
+    }
+
+    /// Write a u32 in big-endian format
+    pub fn write_u32_be(&mut self, value: u32) -> Result<(), WriteError> {
+        self.write_primitive::<u32, BigEndian>(value)
+    }
+
+    /// Write a i32 in big-endian format
+    pub fn write_i32_be(&mut self, value: i32) -> Result<(), WriteError> {
+        self.write_primitive::<i32, BigEndian>(value)
+    }
+
+    /// Write the lower 6-bytes of a u64 (u48) in big-endian format
+    pub fn write_u48_be(&mut self, value: u64) -> Result<(), WriteError> {
+        let bytes = value.to_be_bytes();
+        self.write_bytes(&bytes[2..8])
+    }
+
+    /// Write a u64 in big-endian format
+    pub fn write_u64_be(&mut self, value: u64) -> Result<(), WriteError> {
+        self.write_primitive::<u64, BigEndian>(value)
+    }
+
+    /// Write a i64 in big-endian format
+    pub fn write_i64_be(&mut self, value: i64) -> Result<(), WriteError> {
+        self.write_primitive::<i64, BigEndian>(value)
+    }
+
+    /// Write an IEEE-754 f32 in big endian format
+    pub fn write_f32_be(&mut self, value: f32) -> Result<(), WriteError> {
+        self.write_primitive::<f32, BigEndian>(value)
+    }
+
+    /// Write an IEEE-754 f64 in big endian format
+    pub fn write_f64_be(&mut self, value: f64) -> Result<(), WriteError> {
+        self.write_primitive::<f64, BigEndian>(value)
+    }
+}
+
+/// LEB128 write routines
+impl<'a> WriteCursor<'a> {
+    /// Write a u64 using the minimal unsigned LEB128 encoding
+    pub fn write_uleb128_u64(&mut self, value: u64) -> Result<(), WriteError> {
+        Writer::write_uleb128_u64(self, value)
+    }
+
+    /// Write an i64 using the minimal signed LEB128 encoding
+    pub fn write_sleb128_i64(&mut self, value: i64) -> Result<(), WriteError> {
+        Writer::write_sleb128_i64(self, value)
+    }
+
+    /// Write an i64 using zigzag encoding stored as an unsigned LEB128 value (protobuf `sint64`)
+    pub fn write_zigzag_i64(&mut self, value: i64) -> Result<(), WriteError> {
+        Writer::write_zigzag_i64(self, value)
     }
 }
 
@@ -200,6 +508,7 @@ impl<'a> WriteCursor<'a> {
 mod test {
 
     use super::*;
+    use crate::ReadCursor;
 
     #[test]
     fn transaction_rolls_back_position_on_failure() {
@@ -251,4 +560,255 @@ mod test {
 
         assert_eq!(cursor.written(), &[0x00, 0x00, 0xFF]);
     }
+
+    #[test]
+    fn zeroing_transaction_clears_bytes_written_before_failure() {
+        let mut buffer = [0xEEu8; 6];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor.write_u8(0x01).unwrap();
+
+        let result = cursor.zeroing_transaction(|cur| {
+            cur.write_u16_be(0xCAFE)?;
+            cur.at_pos(0, |cur| cur.write_u8(0x02))?;
+            cur.skip(1)?;
+            cur.write_u32_be(0xDEADBEEF)
+        });
+
+        assert!(result.is_err());
+        assert_eq!(cursor.position(), 1);
+        assert_eq!(
+            cursor.get(0..6).unwrap(),
+            &[0x01, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn zeroing_transaction_restores_back_patched_prefix() {
+        let mut buffer = [0u8; 10];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor.write_bytes(&[1, 2, 3, 4, 5, 6]).unwrap();
+
+        let result = cursor.zeroing_transaction(|cur| {
+            cur.at_pos(0, |cur| cur.write_u8(0xFF))?;
+            cur.write_u8(7)?;
+            cur.write_bytes(&[0xAA; 8])
+        });
+
+        assert!(result.is_err());
+        assert_eq!(cursor.position(), 6);
+        assert_eq!(cursor.get(0..10).unwrap(), &[1, 2, 3, 4, 5, 6, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn restoring_transaction_restores_back_patched_bytes() {
+        let mut buffer = [0u8; 5];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor.write_u16_be(0x0102).unwrap();
+
+        let mut snapshot = [0u8; 2];
+        let result = cursor.restoring_transaction(&mut snapshot, |cur| {
+            cur.write_u16_be(0xCAFE)?;
+            cur.at_pos(0, |cur| cur.write_u16_be(0xFFFF))?;
+            cur.write_u16_be(0xBEEF)
+        });
+
+        assert!(result.is_err());
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.get(0..5).unwrap(), &[0x01, 0x02, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn nested_transactions_undo_inner_writes_when_outer_fails() {
+        let mut buffer = [0u8; 4];
+        let mut cursor = WriteCursor::new(&mut buffer);
+
+        let result = cursor.zeroing_transaction(|cur| {
+            cur.zeroing_transaction(|cur| cur.write_u16_le(0xCAFE))?;
+            cur.write_u8(0xAA)?;
+            cur.write_u16_le(0xBEEF)
+        });
+
+        assert!(result.is_err());
+        assert_eq!(cursor.get(0..4).unwrap(), &[0x00, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn successful_zeroing_transaction_keeps_data() {
+        let mut buffer = [0u8; 2];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor
+            .zeroing_transaction(|cur| cur.write_u16_be(0xCAFE))
+            .unwrap();
+        assert_eq!(cursor.written(), &[0xCA, 0xFE]);
+    }
+
+    #[test]
+    fn big_endian_writes_round_trip_with_reader() {
+        let mut buffer = [0u8; 28];
+        let mut cursor = WriteCursor::new(&mut buffer);
+
+        cursor.write_u16_be(0xCAFE).unwrap();
+        cursor.write_i16_be(-2).unwrap();
+        cursor.write_u32_be(0xDEADBEEF).unwrap();
+        cursor.write_i32_be(i32::MIN).unwrap();
+        cursor.write_u64_be(0x0102030405060708).unwrap();
+        cursor.write_i64_be(-42).unwrap();
+        assert_eq!(cursor.remaining(), 0);
+
+        let mut reader = ReadCursor::new(cursor.written());
+        assert_eq!(reader.read_u16_be().unwrap(), 0xCAFE);
+        assert_eq!(reader.read_i16_be().unwrap(), -2);
+        assert_eq!(reader.read_u32_be().unwrap(), 0xDEADBEEF);
+        assert_eq!(reader.read_i32_be().unwrap(), i32::MIN);
+        assert_eq!(reader.read_u64_be().unwrap(), 0x0102030405060708);
+        assert_eq!(reader.read_i64_be().unwrap(), -42);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn big_endian_writes_produce_expected_bytes() {
+        let mut buffer = [0u8; 18];
+        let mut cursor = WriteCursor::new(&mut buffer);
+
+        cursor.write_u48_be(0x0000AABBCCDDEEFF).unwrap();
+        cursor.write_f32_be(1.0).unwrap();
+        cursor.write_f64_be(f64::MAX).unwrap();
+
+        assert_eq!(
+            cursor.written(),
+            &[
+                0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF, // u48
+                0x3F, 0x80, 0x00, 0x00, // f32
+                0x7F, 0xEF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // f64
+            ]
+        );
+    }
+
+    #[test]
+    fn little_endian_u64_writes_round_trip_with_reader() {
+        let mut buffer = [0u8; 16];
+        let mut cursor = WriteCursor::new(&mut buffer);
+
+        cursor.write_u64_le(0x0100FFEEDDCCBBAA).unwrap();
+        cursor.write_i64_le(i64::MIN).unwrap();
+
+        let mut reader = ReadCursor::new(cursor.written());
+        assert_eq!(reader.read_u64_le().unwrap(), 0x0100FFEEDDCCBBAA);
+        assert_eq!(reader.read_i64_le().unwrap(), i64::MIN);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn can_write_primitive_in_either_byte_order() {
+        fn write_header<E: ByteOrder>(cursor: &mut WriteCursor) {
+            cursor.write_primitive::<u16, E>(0xCAFE).unwrap();
+            cursor.write_primitive::<f32, E>(1.0).unwrap();
+        }
+
+        let mut buffer = [0u8; 6];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        write_header::<BigEndian>(&mut cursor);
+        assert_eq!(cursor.written(), &[0xCA, 0xFE, 0x3F, 0x80, 0x00, 0x00]);
+
+        let mut cursor = WriteCursor::new(&mut buffer);
+        write_header::<LittleEndian>(&mut cursor);
+        assert_eq!(cursor.written(), &[0xFE, 0xCA, 0x00, 0x00, 0x80, 0x3F]);
+    }
+
+    #[test]
+    fn length_prefixed_rejects_body_longer_than_prefix() {
+        let mut buffer = [0u8; 300];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor.write_u8(0xFF).unwrap();
+
+        let result = cursor.write_length_prefixed::<u8, LittleEndian>(|cur| cur.skip(256));
+
+        assert_eq!(result, Err(WriteError::LengthOverflow { length: 256 }));
+        assert_eq!(cursor.written(), &[0xFF]);
+    }
+
+    #[test]
+    fn length_prefixed_rolls_back_prefix_when_body_fails() {
+        let mut buffer = [0u8; 4];
+        let mut cursor = WriteCursor::new(&mut buffer);
+
+        let result = cursor.write_length_prefixed::<u16, LittleEndian>(|cur| cur.write_u32_le(0));
+
+        assert_eq!(
+            result,
+            Err(WriteError::WriteOverflow {
+                remaining: 2,
+                written: 4
+            })
+        );
+        assert_eq!(cursor.position(), 0);
+    }
+
+    #[test]
+    fn length_prefixed_sections_can_be_nested() {
+        let mut buffer = [0u8; 6];
+        let mut cursor = WriteCursor::new(&mut buffer);
+
+        cursor
+            .write_length_prefixed::<u8, BigEndian>(|cur| {
+                cur.write_length_prefixed::<u16, BigEndian>(|cur| cur.write_u16_be(0xCAFE))?;
+                cur.write_u8(0xFF)
+            })
+            .unwrap();
+
+        assert_eq!(cursor.written(), &[0x05, 0x00, 0x02, 0xCA, 0xFE, 0xFF]);
+    }
+
+    #[test]
+    fn leb128_round_trips_with_reader() {
+        let unsigned = [0, 1, 127, 128, 300, 624485, u64::MAX / 2, u64::MAX];
+        let signed = [0, 1, -1, 63, -64, 64, -65, -123456, i64::MIN, i64::MAX];
+
+        let mut buffer = [0u8; 256];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        for value in unsigned {
+            cursor.write_uleb128_u64(value).unwrap();
+        }
+        for value in signed {
+            cursor.write_sleb128_i64(value).unwrap();
+            cursor.write_zigzag_i64(value).unwrap();
+        }
+
+        let mut reader = ReadCursor::new(cursor.written());
+        for value in unsigned {
+            assert_eq!(reader.read_uleb128_u64().unwrap(), value);
+        }
+        for value in signed {
+            assert_eq!(reader.read_sleb128_i64().unwrap(), value);
+            assert_eq!(reader.read_zigzag_i64().unwrap(), value);
+        }
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn leb128_writes_minimal_encoding() {
+        let mut buffer = [0u8; 8];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        cursor.write_uleb128_u64(624485).unwrap();
+        cursor.write_sleb128_i64(-123456).unwrap();
+        cursor.write_sleb128_i64(64).unwrap();
+        assert_eq!(
+            cursor.written(),
+            &[0xE5, 0x8E, 0x26, 0xC0, 0xBB, 0x78, 0xC0, 0x00]
+        );
+    }
+
+    #[test]
+    fn leb128_write_is_atomic() {
+        let mut buffer = [0u8; 2];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        assert_eq!(
+            cursor.write_uleb128_u64(624485),
+            Err(WriteError::WriteOverflow {
+                remaining: 2,
+                written: 3
+            })
+        );
+        assert_eq!(cursor.position(), 0);
+    }
 }
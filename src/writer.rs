@@ -0,0 +1,438 @@
+use crate::{BigEndian, ByteOrder, Encode, LittleEndian, Primitive, WriteError};
+
+/// Sink for encoded data
+///
+/// Sinks only need to provide [`Writer::position`], [`Writer::remaining`],
+/// [`Writer::seek_to`] and [`Writer::write_bytes`]. All of the typed write routines,
+/// transactions and back-patching with [`Writer::at_pos`] are provided as default
+/// methods in terms of these, so encoders written against this trait work with
+/// any sink, e.g. a [`WriteCursor`](crate::WriteCursor) to serialize data or a
+/// [`SizeCursor`](crate::SizeCursor) to measure its length without a buffer.
+///
+/// Implementations of [`Writer::write_bytes`] should either write all of the bytes
+/// or none of them so that the typed routines never leave a partial value behind.
+pub trait Writer {
+    /// Current position of the writer, i.e. the number of bytes written
+    fn position(&self) -> usize;
+
+    /// Number of bytes remaining to be written
+    fn remaining(&self) -> usize;
+
+    /// Seek to an absolute position
+    ///
+    /// Sinks that cannot revisit data once it is written may fail with
+    /// [`WriteError::BadSeek`] for any position other than the current one.
+    fn seek_to(&mut self, pos: usize) -> Result<(), WriteError>;
+
+    /// Write a slice of bytes
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), WriteError>;
+
+    /// Advance the writer a count of bytes
+    fn skip(&mut self, count: usize) -> Result<(), WriteError> {
+        let pos = self
+            .position()
+            .checked_add(count)
+            .ok_or(WriteError::NumericOverflow)?;
+        self.seek_to(pos)
+    }
+
+    /// Perform a write transaction which returns the writer to the original
+    /// position if an error occurs
+    fn transaction<T, R>(&mut self, write: T) -> Result<R, WriteError>
+    where
+        Self: Sized,
+        T: FnOnce(&mut Self) -> Result<R, WriteError>,
+    {
+        let start = self.position();
+        let result = write(self);
+        // if an error occurs, rollback to the starting position
+        if result.is_err() {
+            // seeking back to a position already reached cannot fail
+            let _ = self.seek_to(start);
+        }
+        result
+    }
+
+    /// Perform a write transaction at particular position. The writer is always
+    /// returned to its original position regardless of the success or failure of
+    /// the operation
+    fn at_pos<T, R>(&mut self, pos: usize, write: T) -> Result<R, WriteError>
+    where
+        Self: Sized,
+        T: FnOnce(&mut Self) -> Result<R, WriteError>,
+    {
+        let start = self.position();
+        self.seek_to(pos)?;
+        let result = write(self);
+        // no matter what happens, go back to the starting position
+        self.seek_to(start)?;
+        result
+    }
+
+    /// Write a single u8
+    fn write_u8(&mut self, value: u8) -> Result<(), WriteError> {
+        self.write_bytes(&[value])
+    }
+
+    /// Write a primitive value using the specified byte order
+    fn write_primitive<T, E>(&mut self, value: T) -> Result<(), WriteError>
+    where
+        T: Primitive,
+        E: ByteOrder,
+    {
+        self.write_bytes(E::encode(value).as_ref())
+    }
+
+    /// Encode a value preceded by its encoded length as a primitive of type `T` in byte order `E`
+    ///
+    /// The length is measured using a [`SizeCursor`](crate::SizeCursor) before anything is written.
+    fn write_prefixed<T, E, V>(&mut self, value: &V) -> Result<(), WriteError>
+    where
+        Self: Sized,
+        T: Primitive + TryFrom<usize>,
+        E: ByteOrder,
+        V: Encode + ?Sized,
+    {
+        let length = value.encoded_len()?;
+        let prefix = T::try_from(length).map_err(|_| WriteError::LengthOverflow { length })?;
+        self.write_primitive::<T, E>(prefix)?;
+        value.encode(self)
+    }
+
+    /// Write a section of data preceded by its length
+    ///
+    /// Space for a length prefix of type `T` is reserved and the closure is invoked to
+    /// write the body. The number of bytes written by the closure is then written into
+    /// the prefix using byte order `E` and returned. The operation is performed as a
+    /// transaction so the prefix is also rolled back if the body cannot be written or
+    /// if its length does not fit in `T`.
+    ///
+    /// Unlike [`Writer::write_prefixed`] the body is only written once, but the writer
+    /// must be able to seek back to the prefix.
+    fn write_length_prefixed<T, E>(
+        &mut self,
+        write: impl FnOnce(&mut Self) -> Result<(), WriteError>,
+    ) -> Result<T, WriteError>
+    where
+        Self: Sized,
+        T: Primitive + TryFrom<usize>,
+        E: ByteOrder,
+    {
+        self.transaction(|cur| {
+            let prefix_pos = cur.position();
+            cur.write_primitive::<T, E>(T::default())?;
+            let body_pos = cur.position();
+            write(cur)?;
+            let length = cur
+                .position()
+                .checked_sub(body_pos)
+                .ok_or(WriteError::NumericOverflow)?;
+            let value = T::try_from(length).map_err(|_| WriteError::LengthOverflow { length })?;
+            cur.at_pos(prefix_pos, |cur| cur.write_primitive::<T, E>(value))?;
+            Ok(value)
+        })
+    }
+
+    /// Write a u16 in little-endian format
+    fn write_u16_le(&mut self, value: u16) -> Result<(), WriteError> {
+        self.write_primitive::<u16, LittleEndian>(value)
+    }
+
+    /// Write a i16 in little-endian format
+    fn write_i16_le(&mut self, value: i16) -> Result<(), WriteError> {
+        self.write_primitive::<i16, LittleEndian>(value)
+    }
+
+    /// Write a u32 in little-endian format
+    fn write_u32_le(&mut self, value: u32) -> Result<(), WriteError> {
+        self.write_primitive::<u32, LittleEndian>(value)
+    }
+
+    /// Write a i32 in little-endian format
+    fn write_i32_le(&mut self, value: i32) -> Result<(), WriteError> {
+        self.write_primitive::<i32, LittleEndian>(value)
+    }
+
+    /// Write the lower 6-bytes of a u64 (u48) in little-endian format
+    fn write_u48_le(&mut self, value: u64) -> Result<(), WriteError> {
+        let bytes = value.to_le_bytes();
+        self.write_bytes(&bytes[0..6])
+    }
+
+    /// Write a u64 in little-endian format
+    fn write_u64_le(&mut self, value: u64) -> Result<(), WriteError> {
+        self.write_primitive::<u64, LittleEndian>(value)
+    }
+
+    /// Write a i64 in little-endian format
+    fn write_i64_le(&mut self, value: i64) -> Result<(), WriteError> {
+        self.write_primitive::<i64, LittleEndian>(value)
+    }
+
+    /// Write an IEEE-754 f32 in little-endian format
+    fn write_f32_le(&mut self, value: f32) -> Result<(), WriteError> {
+        self.write_primitive::<f32, LittleEndian>(value)
+    }
+
+    /// Write an IEEE-754 f64 in little-endian format
+    fn write_f64_le(&mut self, value: f64) -> Result<(), WriteError> {
+        self.write_primitive::<f64, LittleEndian>(value)
+    }
+
+    /// Write a u16 in big-endian format
+    fn write_u16_be(&mut self, value: u16) -> Result<(), WriteError> {
+        self.write_primitive::<u16, BigEndian>(value)
+    }
+
+    /// Write a i16 in big-endian format
+    fn write_i16_be(&mut self, value: i16) -> Result<(), WriteError> {
+        self.write_primitive::<i16, BigEndian>(value)
+    }
+
+    /// Write a u32 in big-endian format
+    fn write_u32_be(&mut self, value: u32) -> Result<(), WriteError> {
+        self.write_primitive::<u32, BigEndian>(value)
+    }
+
+    /// Write a i32 in big-endian format
+    fn write_i32_be(&mut self, value: i32) -> Result<(), WriteError> {
+        self.write_primitive::<i32, BigEndian>(value)
+    }
+
+    /// Write the lower 6-bytes of a u64 (u48) in big-endian format
+    fn write_u48_be(&mut self, value: u64) -> Result<(), WriteError> {
+        let bytes = value.to_be_bytes();
+        self.write_bytes(&bytes[2..8])
+    }
+
+    /// Write a u64 in big-endian format
+    fn write_u64_be(&mut self, value: u64) -> Result<(), WriteError> {
+        self.write_primitive::<u64, BigEndian>(value)
+    }
+
+    /// Write a i64 in big-endian format
+    fn write_i64_be(&mut self, value: i64) -> Result<(), WriteError> {
+        self.write_primitive::<i64, BigEndian>(value)
+    }
+
+    /// Write an IEEE-754 f32 in big-endian format
+    fn write_f32_be(&mut self, value: f32) -> Result<(), WriteError> {
+        self.write_primitive::<f32, BigEndian>(value)
+    }
+
+    /// Write an IEEE-754 f64 in big-endian format
+    fn write_f64_be(&mut self, value: f64) -> Result<(), WriteError> {
+        self.write_primitive::<f64, BigEndian>(value)
+    }
+
+    /// Write a u64 using the minimal unsigned LEB128 encoding
+    fn write_uleb128_u64(&mut self, value: u64) -> Result<(), WriteError> {
+        let mut buffer = [0u8; 10];
+        let mut remainder = value;
+        let mut length = 0;
+        for byte in buffer.iter_mut() {
+            *byte = (remainder & 0x7F) as u8;
+            remainder >>= 7;
+            length += 1;
+            if remainder == 0 {
+                break;
+            }
+            *byte |= 0x80;
+        }
+        self.write_bytes(buffer.get(0..length).ok_or(WriteError::NumericOverflow)?)
+    }
+
+    /// Write an i64 using the minimal signed LEB128 encoding
+    fn write_sleb128_i64(&mut self, value: i64) -> Result<(), WriteError> {
+        let mut buffer = [0u8; 10];
+        let mut remainder = value;
+        let mut length = 0;
+        for byte in buffer.iter_mut() {
+            *byte = (remainder & 0x7F) as u8;
+            remainder >>= 7;
+            length += 1;
+            let sign = *byte & 0x40 != 0;
+            if (remainder == 0 && !sign) || (remainder == -1 && sign) {
+                break;
+            }
+            *byte |= 0x80;
+        }
+        self.write_bytes(buffer.get(0..length).ok_or(WriteError::NumericOverflow)?)
+    }
+
+    /// Write an i64 using zigzag encoding stored as an unsigned LEB128 value (protobuf `sint64`)
+    fn write_zigzag_i64(&mut self, value: i64) -> Result<(), WriteError> {
+        self.write_uleb128_u64(((value << 1) ^ (value >> 63)) as u64)
+    }
+
+    /// Write a string of at most `max` bytes followed by a NUL terminator
+    ///
+    /// Strings containing a NUL byte are rejected. Nothing is written on failure.
+    fn write_cstr(&mut self, value: &str, max: usize) -> Result<(), WriteError> {
+        let bytes = value.as_bytes();
+        if let Some(position) = bytes.iter().position(|x| *x == 0) {
+            return Err(WriteError::InteriorNul { position });
+        }
+        check_str_len(bytes.len(), max)?;
+        reserve(self, bytes.len().saturating_add(1))?;
+        self.write_bytes(bytes)?;
+        self.write_u8(0)
+    }
+
+    /// Write a string of at most `max` bytes preceded by its length as a primitive of
+    /// type `T` in byte order `E`
+    ///
+    /// Nothing is written on failure.
+    fn write_str_prefixed<T, E>(&mut self, value: &str, max: usize) -> Result<(), WriteError>
+    where
+        T: Primitive + TryFrom<usize>,
+        E: ByteOrder,
+    {
+        let length = value.len();
+        check_str_len(length, max)?;
+        let prefix = T::try_from(length).map_err(|_| WriteError::LengthOverflow { length })?;
+        reserve(
+            self,
+            E::encode(prefix).as_ref().len().saturating_add(length),
+        )?;
+        self.write_primitive::<T, E>(prefix)?;
+        self.write_bytes(value.as_bytes())
+    }
+
+    /// Write a string into a fixed-width field of `width` bytes, filling the rest of the
+    /// field with `pad`
+    ///
+    /// Nothing is written on failure.
+    fn write_str_padded(&mut self, value: &str, width: usize, pad: u8) -> Result<(), WriteError> {
+        check_str_len(value.len(), width)?;
+        reserve(self, width)?;
+        self.write_bytes(value.as_bytes())?;
+        for _ in value.len()..width {
+            self.write_u8(pad)?;
+        }
+        Ok(())
+    }
+}
+
+fn check_str_len(length: usize, max: usize) -> Result<(), WriteError> {
+    if length > max {
+        return Err(WriteError::StringTooLong { length, max });
+    }
+    Ok(())
+}
+
+/// Fail without writing anything if a multi-part value cannot be written in its entirety
+fn reserve<W: Writer + ?Sized>(writer: &W, count: usize) -> Result<(), WriteError> {
+    if writer.remaining() < count {
+        return Err(WriteError::WriteOverflow {
+            remaining: writer.remaining(),
+            written: count,
+        });
+    }
+    Ok(())
+}
+
+impl<W: Writer + ?Sized> Writer for &mut W {
+    fn position(&self) -> usize {
+        (**self).position()
+    }
+
+    fn remaining(&self) -> usize {
+        (**self).remaining()
+    }
+
+    fn seek_to(&mut self, pos: usize) -> Result<(), WriteError> {
+        (**self).seek_to(pos)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), WriteError> {
+        (**self).write_bytes(bytes)
+    }
+
+    fn write_u8(&mut self, value: u8) -> Result<(), WriteError> {
+        (**self).write_u8(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReadCursor, SizeCursor, WriteCursor};
+
+    fn encode_sample<W: Writer>(mut writer: W) -> Result<(), WriteError> {
+        writer.write_u8(0x01)?;
+        writer.write_u16_be(0x0203)?;
+        writer.write_i32_le(-2)?;
+        writer.write_u48_be(0x0000040506070809)?;
+        writer.write_f32_le(1.0)?;
+        writer.write_uleb128_u64(300)?;
+        writer.write_zigzag_i64(-1)
+    }
+
+    #[test]
+    fn typed_helpers_work_through_generic_writer() {
+        let mut buffer = [0u8; 20];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        encode_sample(&mut cursor).unwrap();
+        assert_eq!(cursor.remaining(), 0);
+
+        let mut reader = ReadCursor::new(cursor.written());
+        assert_eq!(reader.read_u8().unwrap(), 0x01);
+        assert_eq!(reader.read_u16_be().unwrap(), 0x0203);
+        assert_eq!(reader.read_i32_le().unwrap(), -2);
+        assert_eq!(reader.read_u48_be().unwrap(), 0x0000040506070809);
+        assert_eq!(reader.read_f32_le().unwrap(), 1.0);
+        assert_eq!(reader.read_uleb128_u64().unwrap(), 300);
+        assert_eq!(reader.read_zigzag_i64().unwrap(), -1);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn size_cursor_tallies_typed_helpers() {
+        let mut size = SizeCursor::new();
+        encode_sample(&mut size).unwrap();
+        assert_eq!(Writer::position(&size), 20);
+        assert_eq!(Writer::remaining(&size), usize::MAX - 20);
+    }
+
+    fn encode_with_header<W: Writer>(writer: &mut W) -> Result<(), WriteError> {
+        let header = writer.position();
+        writer.skip(2)?;
+        writer.write_bytes(&[0xAA, 0xBB, 0xCC])?;
+        let length = writer.position() - header - 2;
+        writer.at_pos(header, |w| w.write_u16_be(length as u16))?;
+        writer
+            .transaction(|w| {
+                w.write_u8(0xDD)?;
+                w.skip(usize::MAX)
+            })
+            .unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn back_patching_encoder_measures_and_writes() {
+        let mut size = SizeCursor::new();
+        encode_with_header(&mut size).unwrap();
+        assert_eq!(size.len(), 5);
+
+        let mut buffer = [0u8; 5];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        encode_with_header(&mut cursor).unwrap();
+        assert_eq!(cursor.written(), &[0x00, 0x03, 0xAA, 0xBB, 0xCC]);
+    }
+
+    #[test]
+    fn typed_helpers_do_not_write_partial_values() {
+        let mut buffer = [0u8; 3];
+        let mut cursor = WriteCursor::new(&mut buffer);
+        assert_eq!(
+            Writer::write_u32_be(&mut cursor, 0xCAFEBABE),
+            Err(WriteError::WriteOverflow {
+                remaining: 3,
+                written: 4
+            })
+        );
+        assert_eq!(cursor.position(), 0);
+    }
+}